@@ -123,23 +123,65 @@ pub const DIALECTS: &[Dialect] = &[
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum LintName {
+    AmbiguousColumnReference,
+    ConsistentAliasing,
     DisallowNames,
+    DuplicateAlias,
+    ExplicitJoinType,
     ExplicitUnion,
+    FunctionNameCase,
+    IdentifierCase,
+    JoinConditionRequired,
     KeywordCase,
+    LiteralCase,
+    NullComparison,
+    SelectStar,
+    TypeCase,
+    UnusedCte,
+    UnusedTableAlias,
 }
 
 impl LintName {
     pub const fn as_str(&self) -> &'static str {
         match self {
+            LintName::AmbiguousColumnReference => "ambiguous_column_reference",
+            LintName::ConsistentAliasing => "consistent_aliasing",
             LintName::DisallowNames => "disallow_names",
+            LintName::DuplicateAlias => "duplicate_alias",
+            LintName::ExplicitJoinType => "explicit_join_type",
             LintName::ExplicitUnion => "explicit_union",
+            LintName::FunctionNameCase => "function_name_case",
+            LintName::IdentifierCase => "identifier_case",
+            LintName::JoinConditionRequired => "join_condition_required",
             LintName::KeywordCase => "keyword_case",
+            LintName::LiteralCase => "literal_case",
+            LintName::NullComparison => "null_comparison",
+            LintName::SelectStar => "select_star",
+            LintName::TypeCase => "type_case",
+            LintName::UnusedCte => "unused_cte",
+            LintName::UnusedTableAlias => "unused_table_alias",
         }
     }
 }
 
-pub const LINTS: &[LintName] =
-    &[LintName::DisallowNames, LintName::ExplicitUnion, LintName::KeywordCase];
+pub const LINTS: &[LintName] = &[
+    LintName::AmbiguousColumnReference,
+    LintName::ConsistentAliasing,
+    LintName::DisallowNames,
+    LintName::DuplicateAlias,
+    LintName::ExplicitJoinType,
+    LintName::ExplicitUnion,
+    LintName::FunctionNameCase,
+    LintName::IdentifierCase,
+    LintName::JoinConditionRequired,
+    LintName::KeywordCase,
+    LintName::LiteralCase,
+    LintName::NullComparison,
+    LintName::SelectStar,
+    LintName::TypeCase,
+    LintName::UnusedCte,
+    LintName::UnusedTableAlias,
+];
 
 #[derive(Debug, Clone)]
 pub struct LintNameParseError {
@@ -239,10 +281,104 @@ struct LintConfigTable<T> {
     options: T,
 }
 
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OuterKeyword {
+    #[default]
+    Any,
+    Omit,
+    Require,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ExplicitJoinTypeConfig {
+    /// Whether `LEFT`, `RIGHT` and `FULL` joins spell out `OUTER`.
+    pub outer_keyword: OuterKeyword,
+}
+
 #[derive(Debug, Clone, Default, Deserialize, Serialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct ExplicitUnionConfig {}
 
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct UnusedCteConfig {}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct UnusedTableAliasConfig {}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DuplicateAliasConfig {}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NullComparisonConfig {}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AmbiguousColumnReferenceConfig {
+    /// Allow unqualified columns in queries that read from a single table.
+    pub allow_single_source: bool,
+    /// Require qualifying columns with the alias of an aliased table rather than its name.
+    pub require_alias: bool,
+}
+
+impl Default for AmbiguousColumnReferenceConfig {
+    fn default() -> Self {
+        Self { allow_single_source: true, require_alias: false }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct JoinConditionRequiredConfig {
+    /// Allow `CROSS JOIN`, which spells out that every row is paired with every other row.
+    pub allow_cross_join: bool,
+}
+
+impl Default for JoinConditionRequiredConfig {
+    fn default() -> Self {
+        Self { allow_cross_join: true }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AliasStyle {
+    #[default]
+    Explicit,
+    Implicit,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ConsistentAliasingConfig {
+    /// Whether table aliases in `FROM` and joins are written with `AS`.
+    pub table_aliases: AliasStyle,
+    /// Whether aliases in the select list are written with `AS`.
+    pub column_aliases: AliasStyle,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SelectStarConfig {
+    /// Allow wildcards in the outermost query of a statement, whose columns are the result.
+    pub allow_outermost: bool,
+    /// Allow `EXISTS (SELECT * ...)`, where the columns are never read.
+    pub allow_exists: bool,
+    /// Allow `COUNT(*)`.
+    pub allow_count: bool,
+}
+
+impl Default for SelectStarConfig {
+    fn default() -> Self {
+        Self { allow_outermost: false, allow_exists: true, allow_count: true }
+    }
+}
+
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum CapitalisationPolicy {
@@ -256,19 +392,81 @@ pub enum CapitalisationPolicy {
     Camel,
 }
 
+/// Options shared by the lints that enforce the case of keywords, identifiers, function names,
+/// data types and literals.
 #[derive(Debug, Clone, Default, Deserialize, Serialize)]
 #[serde(default, deny_unknown_fields)]
-pub struct KeywordCaseConfig {
+pub struct CapitalisationConfig {
     pub policy: CapitalisationPolicy,
     pub ignore_words: Vec<String>,
     #[serde(
         deserialize_with = "deserialize_ignore_words_regex",
-        serialize_with = "serialize_ignore_words_regex",
-        default
+        serialize_with = "serialize_ignore_words_regex"
     )]
     pub ignore_words_regex: Vec<Regex>,
 }
 
+/// The options of `keyword_case`, the first lint to use them.
+pub type KeywordCaseConfig = CapitalisationConfig;
+
+/// Read the options of the capitalisation lint `lint`, naming it in errors since the options
+/// are the same for all of them.
+fn deserialize_capitalisation_lint<'de, D>(
+    lint: &str,
+    deserializer: D,
+) -> Result<LintConfig<CapitalisationConfig>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    LintConfig::deserialize(deserializer)
+        .map_err(|error| DeError::custom(format!("invalid lints.{lint}: {error}")))
+}
+
+fn deserialize_function_name_case<'de, D>(
+    deserializer: D,
+) -> Result<LintConfig<CapitalisationConfig>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserialize_capitalisation_lint("function_name_case", deserializer)
+}
+
+fn deserialize_identifier_case<'de, D>(
+    deserializer: D,
+) -> Result<LintConfig<CapitalisationConfig>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserialize_capitalisation_lint("identifier_case", deserializer)
+}
+
+fn deserialize_keyword_case<'de, D>(
+    deserializer: D,
+) -> Result<LintConfig<CapitalisationConfig>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserialize_capitalisation_lint("keyword_case", deserializer)
+}
+
+fn deserialize_literal_case<'de, D>(
+    deserializer: D,
+) -> Result<LintConfig<CapitalisationConfig>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserialize_capitalisation_lint("literal_case", deserializer)
+}
+
+fn deserialize_type_case<'de, D>(
+    deserializer: D,
+) -> Result<LintConfig<CapitalisationConfig>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserialize_capitalisation_lint("type_case", deserializer)
+}
+
 fn deserialize_ignore_words_regex<'de, D>(deserializer: D) -> Result<Vec<Regex>, D::Error>
 where
     D: Deserializer<'de>,
@@ -294,8 +492,7 @@ where
                     Ok(regex) => compiled.push(regex),
                     Err(error) => {
                         return Err(DeError::custom(format!(
-                            "invalid lints.keyword_case.ignore_words_regex[{index}] \
-                             (`{pattern}`): {error}"
+                            "ignore_words_regex[{index}] (`{pattern}`): {error}"
                         )));
                     }
                 }
@@ -407,20 +604,209 @@ where
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct Lints {
+    pub ambiguous_column_reference: LintConfig<AmbiguousColumnReferenceConfig>,
+    pub consistent_aliasing: LintConfig<ConsistentAliasingConfig>,
     pub disallow_names: LintConfig<DisallowNamesConfig>,
+    pub duplicate_alias: LintConfig<DuplicateAliasConfig>,
+    pub explicit_join_type: LintConfig<ExplicitJoinTypeConfig>,
     pub explicit_union: LintConfig<ExplicitUnionConfig>,
-    pub keyword_case: LintConfig<KeywordCaseConfig>,
+    #[serde(deserialize_with = "deserialize_function_name_case")]
+    pub function_name_case: LintConfig<CapitalisationConfig>,
+    #[serde(deserialize_with = "deserialize_identifier_case")]
+    pub identifier_case: LintConfig<CapitalisationConfig>,
+    pub join_condition_required: LintConfig<JoinConditionRequiredConfig>,
+    #[serde(deserialize_with = "deserialize_keyword_case")]
+    pub keyword_case: LintConfig<CapitalisationConfig>,
+    #[serde(deserialize_with = "deserialize_literal_case")]
+    pub literal_case: LintConfig<CapitalisationConfig>,
+    pub null_comparison: LintConfig<NullComparisonConfig>,
+    pub select_star: LintConfig<SelectStarConfig>,
+    #[serde(deserialize_with = "deserialize_type_case")]
+    pub type_case: LintConfig<CapitalisationConfig>,
+    pub unused_cte: LintConfig<UnusedCteConfig>,
+    pub unused_table_alias: LintConfig<UnusedTableAliasConfig>,
+}
+
+impl Lints {
+    /// The level `lint` reports at.
+    pub fn level_mut(&mut self, lint: LintName) -> &mut Severity {
+        match lint {
+            LintName::AmbiguousColumnReference => &mut self.ambiguous_column_reference.level,
+            LintName::ConsistentAliasing => &mut self.consistent_aliasing.level,
+            LintName::DisallowNames => &mut self.disallow_names.level,
+            LintName::DuplicateAlias => &mut self.duplicate_alias.level,
+            LintName::ExplicitJoinType => &mut self.explicit_join_type.level,
+            LintName::ExplicitUnion => &mut self.explicit_union.level,
+            LintName::FunctionNameCase => &mut self.function_name_case.level,
+            LintName::IdentifierCase => &mut self.identifier_case.level,
+            LintName::JoinConditionRequired => &mut self.join_condition_required.level,
+            LintName::KeywordCase => &mut self.keyword_case.level,
+            LintName::LiteralCase => &mut self.literal_case.level,
+            LintName::NullComparison => &mut self.null_comparison.level,
+            LintName::SelectStar => &mut self.select_star.level,
+            LintName::TypeCase => &mut self.type_case.level,
+            LintName::UnusedCte => &mut self.unused_cte.level,
+            LintName::UnusedTableAlias => &mut self.unused_table_alias.level,
+        }
+    }
 }
 
 impl Default for Lints {
     fn default() -> Self {
         Self {
+            ambiguous_column_reference: LintConfig {
+                level: Severity::Allow,
+                options: AmbiguousColumnReferenceConfig::default(),
+            },
+            consistent_aliasing: LintConfig {
+                level: Severity::Allow,
+                options: ConsistentAliasingConfig::default(),
+            },
             disallow_names: LintConfig::default(),
+            duplicate_alias: LintConfig {
+                level: Severity::Allow,
+                options: DuplicateAliasConfig::default(),
+            },
+            explicit_join_type: LintConfig {
+                level: Severity::Allow,
+                options: ExplicitJoinTypeConfig::default(),
+            },
             explicit_union: LintConfig::default(),
+            function_name_case: LintConfig {
+                level: Severity::Allow,
+                options: CapitalisationConfig::default(),
+            },
+            identifier_case: LintConfig {
+                level: Severity::Allow,
+                options: CapitalisationConfig::default(),
+            },
+            join_condition_required: LintConfig {
+                level: Severity::Allow,
+                options: JoinConditionRequiredConfig::default(),
+            },
             keyword_case: LintConfig {
                 level: Severity::Allow,
-                options: KeywordCaseConfig::default(),
+                options: CapitalisationConfig::default(),
             },
+            literal_case: LintConfig {
+                level: Severity::Allow,
+                options: CapitalisationConfig::default(),
+            },
+            null_comparison: LintConfig {
+                level: Severity::Allow,
+                options: NullComparisonConfig::default(),
+            },
+            select_star: LintConfig {
+                level: Severity::Allow,
+                options: SelectStarConfig::default(),
+            },
+            type_case: LintConfig {
+                level: Severity::Allow,
+                options: CapitalisationConfig::default(),
+            },
+            unused_cte: LintConfig { level: Severity::Allow, options: UnusedCteConfig::default() },
+            unused_table_alias: LintConfig {
+                level: Severity::Allow,
+                options: UnusedTableAliasConfig::default(),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IndentStyle {
+    #[default]
+    Spaces,
+    Tabs,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CommaStyle {
+    #[default]
+    Trailing,
+    Leading,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OperatorStyle {
+    #[default]
+    Leading,
+    Trailing,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CaseStyle {
+    #[default]
+    Preserve,
+    Upper,
+    Lower,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SemicolonStyle {
+    #[default]
+    Preserve,
+    Always,
+    Never,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Format {
+    pub indent_width: usize,
+    pub indent_style: IndentStyle,
+    pub max_line_width: usize,
+    pub comma_style: CommaStyle,
+    /// Whether boolean, arithmetic and `||` chains that do not fit break before or after their
+    /// operators.
+    pub operator_style: OperatorStyle,
+    pub keyword_case: CaseStyle,
+    pub identifier_case: CaseStyle,
+    /// Number of blank lines between statements. When unset, a single blank line is kept
+    /// wherever the source has at least one.
+    pub blank_lines_between_statements: Option<usize>,
+    /// Number of blank lines between the common table expressions of a `WITH` clause and
+    /// before its query. When unset, a single blank line is kept wherever the source has one.
+    pub blank_lines_between_ctes: Option<usize>,
+    /// Whether statements end with a semicolon. `never` keeps the semicolons the dialect needs
+    /// to tell statements apart.
+    pub semicolons: SemicolonStyle,
+    /// Line up aliases in select lists, comparison operators in `AND` / `OR` chains, `THEN` in
+    /// `CASE` arms, the names and types of `CREATE TABLE` columns and the columns of `VALUES`
+    /// rows when they are laid out one per line.
+    pub align: bool,
+    /// The most spaces added to line up a column. Items that would need more are left as is.
+    pub max_align_padding: usize,
+    /// `VALUES` lists with more rows than this are kept as written, which saves time on large
+    /// seed files.
+    pub max_values_rows: usize,
+    /// Reparse the output and check that it keeps every token and comment of the source and
+    /// formats to itself, failing instead of emitting changed SQL.
+    pub verify: bool,
+}
+
+impl Default for Format {
+    fn default() -> Self {
+        Self {
+            indent_width: 4,
+            indent_style: IndentStyle::default(),
+            max_line_width: 80,
+            comma_style: CommaStyle::default(),
+            operator_style: OperatorStyle::default(),
+            keyword_case: CaseStyle::default(),
+            identifier_case: CaseStyle::default(),
+            blank_lines_between_statements: None,
+            blank_lines_between_ctes: None,
+            semicolons: SemicolonStyle::default(),
+            align: false,
+            max_align_padding: 16,
+            max_values_rows: 1000,
+            verify: true,
         }
     }
 }
@@ -430,6 +816,7 @@ impl Default for Lints {
 pub struct Config {
     pub core: Core,
     pub lints: Lints,
+    pub format: Format,
 }
 
 #[derive(Debug)]
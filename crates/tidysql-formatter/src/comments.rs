@@ -0,0 +1,167 @@
+use std::collections::HashMap;
+
+use tidysql_syntax::{SyntaxElement, SyntaxKind, SyntaxToken, SyntaxTree, TokenId};
+
+use crate::directives::Verbatim;
+use crate::doc::Doc;
+use crate::tokens::{is_code, is_comment};
+
+#[derive(Clone)]
+pub(crate) struct Comment {
+    pub(crate) token: SyntaxToken,
+    /// The comment starts its own line rather than following code on the same line.
+    pub(crate) own_line: bool,
+    pub(crate) blank_line_before: bool,
+    pub(crate) newline_after: bool,
+    pub(crate) blank_line_after: bool,
+    /// The comment is a format directive on the boundary of a verbatim region.
+    pub(crate) mark: bool,
+}
+
+impl Comment {
+    fn new(text: &str, token: SyntaxToken, verbatim: &Verbatim) -> Self {
+        let range = token.text_range();
+        let before = &text[..usize::from(range.start())];
+        let after = &text[usize::from(range.end())..];
+
+        let leading_whitespace = &before[before.trim_end().len()..];
+        let newlines_before = leading_whitespace.matches('\n').count();
+        let own_line = newlines_before > 0 || before.trim_end().is_empty();
+        let trailing_whitespace = &after[..after.len() - after.trim_start().len()];
+        let newlines_after = trailing_whitespace.matches('\n').count();
+        let newline_after = newlines_after > 0 || after.trim_start().is_empty();
+
+        Self {
+            own_line,
+            blank_line_before: newlines_before > 1,
+            newline_after,
+            blank_line_after: newlines_after > 1,
+            mark: verbatim.is_marked(&token),
+            token,
+        }
+    }
+
+    pub(crate) fn text(&self) -> &str {
+        self.token.text()
+    }
+
+    /// Line comments run to the end of the line, so whatever follows must start a new one.
+    pub(crate) fn is_line_comment(&self) -> bool {
+        self.token.kind() != SyntaxKind::BlockComment && !self.text().starts_with("/*")
+    }
+}
+
+/// Comments of the tree, each attached to the code token it belongs to.
+///
+/// A comment that follows code on the same line trails that code; every other comment leads
+/// the next code token. Comments after the last code token are dangling and are printed at the
+/// end of the file.
+#[derive(Default)]
+pub(crate) struct CommentMap {
+    leading: HashMap<TokenId, Vec<Comment>>,
+    trailing: HashMap<TokenId, Vec<Comment>>,
+    dangling: Vec<Comment>,
+}
+
+impl CommentMap {
+    pub(crate) fn build(tree: &SyntaxTree, verbatim: &Verbatim) -> Self {
+        let text = tree.text();
+        let mut map = CommentMap::default();
+        let mut previous: Option<TokenId> = None;
+        let mut pending: Vec<Comment> = Vec::new();
+
+        for element in tree.root().descendants_with_tokens() {
+            let SyntaxElement::Token(token) = element else { continue };
+
+            for trivia in token.leading_trivia() {
+                map.attach(text, trivia, verbatim, previous, &mut pending);
+            }
+
+            if is_code(&token) {
+                if !pending.is_empty() {
+                    map.leading.insert(token.id(), std::mem::take(&mut pending));
+                }
+                previous = Some(token.id());
+            }
+
+            for trivia in token.trailing_trivia() {
+                map.attach(text, trivia, verbatim, previous, &mut pending);
+            }
+        }
+
+        map.dangling = pending;
+        map
+    }
+
+    fn attach(
+        &mut self,
+        text: &str,
+        trivia: SyntaxToken,
+        verbatim: &Verbatim,
+        previous: Option<TokenId>,
+        pending: &mut Vec<Comment>,
+    ) {
+        if !is_comment(trivia.kind()) {
+            return;
+        }
+
+        let comment = Comment::new(text, trivia, verbatim);
+        match previous {
+            Some(previous) if !comment.own_line && pending.is_empty() => {
+                self.trailing.entry(previous).or_default().push(comment);
+            }
+            _ => pending.push(comment),
+        }
+    }
+
+    pub(crate) fn leading(&self, token: &SyntaxToken) -> &[Comment] {
+        self.leading.get(&token.id()).map_or(&[], Vec::as_slice)
+    }
+
+    pub(crate) fn trailing(&self, token: &SyntaxToken) -> &[Comment] {
+        self.trailing.get(&token.id()).map_or(&[], Vec::as_slice)
+    }
+
+    pub(crate) fn dangling(&self) -> &[Comment] {
+        &self.dangling
+    }
+}
+
+pub(crate) fn leading_comments_doc(comments: &[Comment]) -> Doc {
+    let mut parts = Vec::new();
+    for comment in comments {
+        if comment.own_line {
+            parts.push(line_before(comment));
+        }
+        parts.push(comment_text_doc(comment));
+        if comment.blank_line_after {
+            parts.push(Doc::empty_line());
+        } else if comment.is_line_comment() || comment.newline_after {
+            parts.push(Doc::hard_line());
+        } else {
+            parts.push(Doc::space());
+        }
+    }
+    Doc::concat(parts)
+}
+
+pub(crate) fn trailing_comments_doc(comments: &[Comment]) -> Doc {
+    let mut parts = Vec::new();
+    for comment in comments {
+        parts.push(Doc::space());
+        parts.push(comment_text_doc(comment));
+        if comment.is_line_comment() {
+            parts.push(Doc::hard_line());
+        }
+    }
+    Doc::concat(parts)
+}
+
+fn comment_text_doc(comment: &Comment) -> Doc {
+    let text = Doc::text(comment.text());
+    if comment.mark { Doc::concat(vec![text, Doc::Mark]) } else { text }
+}
+
+fn line_before(comment: &Comment) -> Doc {
+    if comment.blank_line_before { Doc::empty_line() } else { Doc::hard_line() }
+}
@@ -0,0 +1,122 @@
+use tidysql_syntax::{DialectKind, SyntaxElement, SyntaxKind, SyntaxNode};
+
+use crate::tokens::{first_code_token, is_keyword, last_code_token};
+
+/// How the body of a [`DialectRules::clauses`] keyword is laid out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ClauseBody {
+    /// A comma separated list, one item per line when it does not fit, like `SELECT`.
+    List,
+    /// A single expression that follows the keyword, like `WHERE`.
+    Single,
+}
+
+/// Layout rules for constructs that only exist in some dialects.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct DialectRules {
+    /// Keywords that start a clause of their own in a statement, such as ClickHouse
+    /// `SETTINGS` and `FORMAT`, whether or not the parser gives the clause a node.
+    pub(crate) clauses: &'static [(&'static str, ClauseBody)],
+    /// Types written with angle brackets, such as BigQuery `STRUCT<a INT64>` and
+    /// `ARRAY<STRING>`, which break like parentheses.
+    pub(crate) angle_bracket_types: bool,
+    /// `SELECT TOP n`, which stays on the line of the `SELECT` keyword.
+    pub(crate) select_top: bool,
+    /// The batch separator of scripts, T-SQL `GO`, which sits on a line of its own followed by
+    /// a blank line.
+    pub(crate) batch_separator: Option<&'static str>,
+    /// Statements need no terminator to tell them apart, as in T-SQL, so every semicolon can be
+    /// removed rather than only the last one of the file.
+    pub(crate) optional_terminators: bool,
+    /// The call that turns a table function into a table, Snowflake `TABLE(FLATTEN(...))`, whose
+    /// brackets hug the call inside rather than breaking around it.
+    pub(crate) table_wrapper: Option<&'static str>,
+}
+
+impl DialectRules {
+    pub(crate) fn new(dialect: DialectKind) -> Self {
+        match dialect {
+            DialectKind::Bigquery | DialectKind::Databricks | DialectKind::Sparksql => {
+                Self { angle_bracket_types: true, ..Self::default() }
+            }
+            DialectKind::Clickhouse => Self {
+                clauses: &[("settings", ClauseBody::List), ("format", ClauseBody::Single)],
+                ..Self::default()
+            },
+            // `LATERAL FLATTEN(...)` breaks like any other call in the `FROM` list; only its
+            // `TABLE(FLATTEN(...))` spelling needs a rule.
+            DialectKind::Snowflake => {
+                Self { select_top: true, table_wrapper: Some("table"), ..Self::default() }
+            }
+            DialectKind::Tsql => Self {
+                select_top: true,
+                batch_separator: Some("go"),
+                optional_terminators: true,
+                ..Self::default()
+            },
+            _ => Self::default(),
+        }
+    }
+
+    /// The layout of the clause `element` starts, if it starts one of [`Self::clauses`].
+    pub(crate) fn clause(&self, element: &SyntaxElement) -> Option<ClauseBody> {
+        let token = first_code_token(element)?;
+        if token.kind() != SyntaxKind::Keyword {
+            return None;
+        }
+        self.clauses
+            .iter()
+            .find(|(keyword, _)| token.text().eq_ignore_ascii_case(keyword))
+            .map(|&(_, body)| body)
+    }
+
+    /// Whether `element` is the batch separator keyword, on its own or wrapped in a node.
+    pub(crate) fn is_batch_separator(&self, element: &SyntaxElement) -> bool {
+        let (Some(keyword), Some(first), Some(last)) =
+            (self.batch_separator, first_code_token(element), last_code_token(element))
+        else {
+            return false;
+        };
+        first.id() == last.id() && is_keyword(&SyntaxElement::Token(first), keyword)
+    }
+
+    /// Whether `node` groups the statements of a batch, which are laid out like the statements
+    /// of a file.
+    pub(crate) fn is_batch(&self, node: &SyntaxNode) -> bool {
+        self.batch_separator.is_some()
+            && node.parent().is_some_and(|parent| parent.kind() == SyntaxKind::File)
+            && node.children().any(|child| child.kind() == SyntaxKind::Statement)
+    }
+
+    /// Whether `node` is the bracketed argument of [`Self::table_wrapper`] and holds nothing but
+    /// one function call.
+    pub(crate) fn hugs_call(&self, node: &SyntaxNode) -> bool {
+        let Some(wrapper) = self.table_wrapper else {
+            return false;
+        };
+        let callee =
+            std::iter::successors(node.first_token().prev_token(), |token| token.prev_token())
+                .find(|token| !token.text().is_empty());
+        if !callee.is_some_and(|token| token.text().eq_ignore_ascii_case(wrapper)) {
+            return false;
+        }
+
+        let mut arguments = node.children_with_tokens().filter(|child| {
+            first_code_token(child).is_some_and(|token| {
+                !matches!(token.kind(), SyntaxKind::StartBracket | SyntaxKind::EndBracket)
+            })
+        });
+        matches!(
+            (arguments.next(), arguments.next()),
+            (Some(SyntaxElement::Node(call)), None) if call.kind() == SyntaxKind::Function
+        )
+    }
+
+    /// Whether `node` is a type whose parameters are enclosed in angle brackets.
+    pub(crate) fn is_angle_bracketed(&self, node: &SyntaxNode) -> bool {
+        self.angle_bracket_types
+            && node.children_with_tokens().any(|child| {
+                child.as_token().is_some_and(|token| token.kind() == SyntaxKind::StartAngleBracket)
+            })
+    }
+}
@@ -0,0 +1,237 @@
+use std::collections::HashSet;
+
+use tidysql_syntax::{
+    SyntaxElement, SyntaxKind, SyntaxNode, SyntaxToken, SyntaxTree, TextLen, TextRange, TextSize,
+    TokenId,
+};
+
+use crate::tokens::{first_code_token, is_code, is_comment, is_delimiter_command, last_code_token};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Directive {
+    Off,
+    On,
+    Skip,
+}
+
+/// Parse `-- tidysql: format off`, `format on` and `format skip` comments.
+fn parse_directive(comment: &str) -> Option<Directive> {
+    let body = comment.strip_prefix("--")?.trim();
+    let body = body.strip_prefix("tidysql:")?.trim();
+    let mut words = body.split_whitespace();
+    if words.next()? != "format" {
+        return None;
+    }
+
+    let directive = match words.next()? {
+        "off" => Directive::Off,
+        "on" => Directive::On,
+        "skip" => Directive::Skip,
+        _ => return None,
+    };
+    words.next().is_none().then_some(directive)
+}
+
+/// A part of the source that is copied to the output as is.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Region {
+    /// Source text between the directive and the end of the region.
+    pub(crate) source: TextRange,
+    /// Length of the text printed between the end of `source` and the mark that closes the
+    /// region, i.e. the `format on` comment. `None` when the region runs to the end of the file.
+    pub(crate) end_text_len: Option<usize>,
+}
+
+/// Regions protected by format directives, and statements that are always kept as written.
+///
+/// A region starts right after a `format off` or `format skip` comment and ends right before
+/// the matching `format on` comment, at the end of the statement following `format skip`, or
+/// at the end of the file when a `format off` is never closed. The layout places a mark after
+/// the comment or token on either side of every region so that the printed text between the
+/// marks can be replaced with the source.
+///
+/// Statements with a procedural body and MySQL `DELIMITER` commands are regions of their own,
+/// from their first to their last code token, with a mark before the first token instead.
+#[derive(Default)]
+pub(crate) struct Verbatim {
+    pub(crate) regions: Vec<Region>,
+    /// Comments and tokens whose text is followed by a mark.
+    pub(crate) marks: HashSet<TokenId>,
+    /// Tokens whose text is preceded by a mark.
+    pub(crate) marks_before: HashSet<TokenId>,
+    /// The `format off` comment of a region that is never closed.
+    pub(crate) unclosed: Option<TextRange>,
+}
+
+impl Verbatim {
+    pub(crate) fn build(tree: &SyntaxTree) -> Self {
+        let mut verbatim = Verbatim::default();
+        let mut off: Option<SyntaxToken> = None;
+        let mut skip: Option<SyntaxToken> = None;
+        // Directives inside a skipped statement are part of its verbatim text.
+        let mut skipped_until = TextSize::default();
+
+        for element in tree.root().descendants_with_tokens() {
+            let SyntaxElement::Token(token) = element else { continue };
+
+            for trivia in token.leading_trivia() {
+                if trivia.text_range().start() >= skipped_until {
+                    verbatim.directive(trivia, &mut off, &mut skip);
+                }
+            }
+
+            if is_code(&token)
+                && let Some(comment) = skip.take()
+                && let Some(end) = statement(&token)
+                    .and_then(|statement| last_code_token(&SyntaxElement::Node(statement)))
+            {
+                let source = TextRange::new(comment.text_range().end(), end.text_range().end());
+                verbatim.regions.push(Region { source, end_text_len: Some(0) });
+                verbatim.marks.insert(comment.id());
+                verbatim.marks.insert(end.id());
+                skipped_until = end.text_range().end();
+            } else if is_code(&token)
+                && off.is_none()
+                && token.text_range().start() >= skipped_until
+                && let Some(statement) = outermost_statement(&token)
+                && let Some(first) = first_code_token(&SyntaxElement::Node(statement.clone()))
+                && first.id() == token.id()
+                && kept_as_written(&statement)
+                && let Some(end) = last_code_token(&SyntaxElement::Node(statement))
+            {
+                let source = TextRange::new(token.text_range().start(), end.text_range().end());
+                verbatim.regions.push(Region { source, end_text_len: Some(0) });
+                verbatim.marks_before.insert(token.id());
+                verbatim.marks.insert(end.id());
+                skipped_until = end.text_range().end();
+            }
+
+            for trivia in token.trailing_trivia() {
+                if trivia.text_range().start() >= skipped_until {
+                    verbatim.directive(trivia, &mut off, &mut skip);
+                }
+            }
+        }
+
+        if let Some(comment) = off {
+            let source = TextRange::new(comment.text_range().end(), tree.text().text_len());
+            verbatim.regions.push(Region { source, end_text_len: None });
+            verbatim.marks.insert(comment.id());
+            verbatim.unclosed = Some(comment.text_range());
+        }
+
+        verbatim
+    }
+
+    fn directive(
+        &mut self,
+        trivia: SyntaxToken,
+        off: &mut Option<SyntaxToken>,
+        skip: &mut Option<SyntaxToken>,
+    ) {
+        if !is_comment(trivia.kind()) {
+            return;
+        }
+
+        match (parse_directive(trivia.text()), off.as_ref()) {
+            (Some(Directive::Off), None) => {
+                *skip = None;
+                *off = Some(trivia);
+            }
+            (Some(Directive::On), Some(start)) => {
+                let source = TextRange::new(start.text_range().end(), trivia.text_range().start());
+                self.regions.push(Region { source, end_text_len: Some(trivia.text().len()) });
+                self.marks.insert(start.id());
+                self.marks.insert(trivia.id());
+                *off = None;
+            }
+            (Some(Directive::Skip), None) => *skip = Some(trivia),
+            _ => {}
+        }
+    }
+
+    pub(crate) fn is_marked(&self, token: &SyntaxToken) -> bool {
+        self.marks.contains(&token.id())
+    }
+
+    pub(crate) fn is_marked_before(&self, token: &SyntaxToken) -> bool {
+        self.marks_before.contains(&token.id())
+    }
+
+    /// Replace the printed text of every region with its source text. `marks` are the output
+    /// offsets the printer recorded, in order.
+    pub(crate) fn splice(&self, source: &str, printed: String, marks: &[usize]) -> String {
+        let expected: usize =
+            self.regions.iter().map(|region| 1 + usize::from(region.end_text_len.is_some())).sum();
+        let ordered = marks.windows(2).all(|pair| pair[0] <= pair[1]);
+        if self.regions.is_empty() || marks.len() != expected || !ordered {
+            return printed;
+        }
+
+        let mut output = String::with_capacity(printed.len());
+        let mut cursor = 0;
+        let mut marks = marks.iter().copied();
+
+        for region in &self.regions {
+            let Some(start) = marks.next() else { break };
+            output.push_str(&printed[cursor..start]);
+            output.push_str(&source[region.source]);
+
+            match region.end_text_len {
+                Some(len) => {
+                    let Some(end) = marks.next() else { break };
+                    cursor = end.saturating_sub(len).max(start);
+                }
+                None => {
+                    cursor = printed.len();
+                    break;
+                }
+            }
+        }
+
+        output.push_str(&printed[cursor..]);
+        output
+    }
+}
+
+/// The outermost statement `token` belongs to, which is a statement of the file or of a batch.
+fn outermost_statement(token: &SyntaxToken) -> Option<SyntaxNode> {
+    token.parent().ancestors().filter(|node| node.kind() == SyntaxKind::Statement).last()
+}
+
+/// Whether `statement` is laid out exactly as written: statements with a procedural body, which
+/// holds statements of its own or a `BEGIN ... END` block, and `DELIMITER` commands, whose
+/// argument runs to the end of the line.
+fn kept_as_written(statement: &SyntaxNode) -> bool {
+    if is_delimiter_command(&SyntaxElement::Node(statement.clone())) {
+        return true;
+    }
+
+    let mut begin = false;
+    for element in statement.descendants_with_tokens() {
+        let SyntaxElement::Token(token) = element else { continue };
+        if token.kind() == SyntaxKind::StatementTerminator {
+            return true;
+        }
+        if token.kind() != SyntaxKind::Keyword {
+            continue;
+        }
+        if token.text().eq_ignore_ascii_case("begin") {
+            begin = true;
+        } else if begin
+            && token.text().eq_ignore_ascii_case("end")
+            && !token.parent().ancestors().any(|node| node.kind() == SyntaxKind::CaseExpression)
+        {
+            return true;
+        }
+    }
+    false
+}
+
+/// The top-level statement `token` belongs to.
+fn statement(token: &SyntaxToken) -> Option<SyntaxNode> {
+    token
+        .parent()
+        .ancestors()
+        .find(|node| node.parent().is_some_and(|parent| parent.kind() == SyntaxKind::File))
+}
@@ -0,0 +1,166 @@
+/// Document IR consumed by the printer.
+///
+/// Layout code describes the preferred shape of the output with groups, indentation and line
+/// break opportunities; the printer decides which groups fit on a single line.
+#[derive(Debug, Clone)]
+pub(crate) enum Doc {
+    Nil,
+    Text(String),
+    Line(LineKind),
+    Concat(Vec<Doc>),
+    Indent(Box<Doc>),
+    /// Prints `doc` with the indentation of the line it starts on rather than that of the
+    /// enclosing `Indent` docs.
+    Anchored(Box<Doc>),
+    Group {
+        doc: Box<Doc>,
+        should_break: bool,
+    },
+    /// Records the current output offset, see [`crate::directives::Verbatim`]. After a line
+    /// break, the offset is that of the text on the next line.
+    Mark,
+    /// Prints `doc`, then pads the line so that it ends at the same column as the other
+    /// `Align` docs of the same set.
+    Align {
+        set: usize,
+        doc: Box<Doc>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum LineKind {
+    /// Prints nothing when flat, a newline when broken.
+    Soft,
+    /// Prints a space when flat, a newline when broken.
+    Space,
+    /// Always prints a newline.
+    Hard,
+    /// Always prints a newline followed by the given number of empty lines.
+    Blank(usize),
+}
+
+impl Doc {
+    pub(crate) fn text(text: impl Into<String>) -> Doc {
+        Doc::Text(text.into())
+    }
+
+    pub(crate) fn space() -> Doc {
+        Doc::Text(" ".to_string())
+    }
+
+    pub(crate) fn line() -> Doc {
+        Doc::Line(LineKind::Space)
+    }
+
+    pub(crate) fn soft_line() -> Doc {
+        Doc::Line(LineKind::Soft)
+    }
+
+    pub(crate) fn hard_line() -> Doc {
+        Doc::Line(LineKind::Hard)
+    }
+
+    pub(crate) fn empty_line() -> Doc {
+        Doc::Line(LineKind::Blank(1))
+    }
+
+    pub(crate) fn blank_lines(count: usize) -> Doc {
+        Doc::Line(LineKind::Blank(count))
+    }
+
+    pub(crate) fn concat(docs: Vec<Doc>) -> Doc {
+        Doc::Concat(docs)
+    }
+
+    pub(crate) fn indent(doc: Doc) -> Doc {
+        Doc::Indent(Box::new(doc))
+    }
+
+    pub(crate) fn anchored(doc: Doc) -> Doc {
+        Doc::Anchored(Box::new(doc))
+    }
+
+    pub(crate) fn align(set: usize, doc: Doc) -> Doc {
+        Doc::Align { set, doc: Box::new(doc) }
+    }
+
+    /// Wrap `doc` in a group. Groups with a hard line break before their end can never be
+    /// printed flat; a hard line break at the very end, such as the one after a trailing line
+    /// comment, only ends the line the group is printed on.
+    pub(crate) fn group(doc: Doc) -> Doc {
+        let should_break = doc.hard_break() == HardBreak::Inner;
+        Doc::Group { doc: Box::new(doc), should_break }
+    }
+
+    /// Wrap `doc` in a group that is always printed broken.
+    pub(crate) fn broken_group(doc: Doc) -> Doc {
+        Doc::Group { doc: Box::new(doc), should_break: true }
+    }
+
+    /// The width of `doc` printed on a single line, or `None` when it cannot be.
+    pub(crate) fn flat_width(&self) -> Option<usize> {
+        match self {
+            Doc::Nil | Doc::Mark | Doc::Line(LineKind::Soft) => Some(0),
+            Doc::Line(LineKind::Space) => Some(1),
+            Doc::Line(LineKind::Hard | LineKind::Blank(_)) => None,
+            Doc::Text(text) => (!text.contains('\n')).then(|| text.chars().count()),
+            Doc::Concat(docs) => docs.iter().map(Doc::flat_width).sum(),
+            Doc::Group { should_break: true, .. } => None,
+            Doc::Indent(doc)
+            | Doc::Anchored(doc)
+            | Doc::Group { doc, .. }
+            | Doc::Align { doc, .. } => doc.flat_width(),
+        }
+    }
+
+    fn hard_break(&self) -> HardBreak {
+        match self {
+            Doc::Nil | Doc::Text(_) | Doc::Mark => HardBreak::None,
+            Doc::Line(LineKind::Soft | LineKind::Space) => HardBreak::None,
+            Doc::Line(LineKind::Hard | LineKind::Blank(_)) => HardBreak::Trailing,
+            Doc::Concat(docs) => {
+                let mut state = HardBreak::None;
+                for doc in docs {
+                    if state == HardBreak::Trailing && doc.has_text() {
+                        return HardBreak::Inner;
+                    }
+                    match doc.hard_break() {
+                        HardBreak::None => {}
+                        HardBreak::Trailing => state = HardBreak::Trailing,
+                        HardBreak::Inner => return HardBreak::Inner,
+                    }
+                }
+                state
+            }
+            Doc::Indent(doc) | Doc::Anchored(doc) | Doc::Align { doc, .. } => doc.hard_break(),
+            Doc::Group { doc, should_break } => {
+                if *should_break {
+                    HardBreak::Inner
+                } else {
+                    doc.hard_break()
+                }
+            }
+        }
+    }
+
+    fn has_text(&self) -> bool {
+        match self {
+            Doc::Nil | Doc::Line(_) | Doc::Mark => false,
+            Doc::Text(text) => !text.is_empty(),
+            Doc::Concat(docs) => docs.iter().any(Doc::has_text),
+            Doc::Indent(doc)
+            | Doc::Anchored(doc)
+            | Doc::Group { doc, .. }
+            | Doc::Align { doc, .. } => doc.has_text(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HardBreak {
+    None,
+    /// The document ends with a hard line break.
+    Trailing,
+    /// The document has a hard line break followed by more text.
+    Inner,
+}
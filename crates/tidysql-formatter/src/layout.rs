@@ -0,0 +1,1349 @@
+use std::borrow::Cow;
+use std::cell::{Cell, RefCell};
+use std::collections::HashSet;
+
+use tidysql_config::{CaseStyle, CommaStyle, Format, OperatorStyle, SemicolonStyle};
+use tidysql_syntax::{
+    DialectKind, SyntaxElement, SyntaxKind, SyntaxNode, SyntaxToken, SyntaxTree, TextRange, TokenId,
+};
+
+use crate::comments::{Comment, CommentMap, leading_comments_doc, trailing_comments_doc};
+use crate::dialect::{ClauseBody, DialectRules};
+use crate::directives::Verbatim;
+use crate::doc::Doc;
+use crate::table;
+use crate::tokens::{
+    case_style, first_code_token, is_code, is_delimiter_command, is_keyword, last_code_token,
+    node_kind, token_kind,
+};
+
+pub(crate) struct Formatter<'a> {
+    tree: &'a SyntaxTree,
+    dialect: DialectKind,
+    format: &'a Format,
+    rules: DialectRules,
+    verbatim: &'a Verbatim,
+    comments: CommentMap,
+    /// Tokens whose leading or trailing comments have already been laid out, possibly away from
+    /// the token itself.
+    printed_leading: RefCell<HashSet<TokenId>>,
+    printed_trailing: RefCell<HashSet<TokenId>>,
+    align_sets: Cell<usize>,
+}
+
+impl<'a> Formatter<'a> {
+    pub(crate) fn new(
+        tree: &'a SyntaxTree,
+        dialect: DialectKind,
+        format: &'a Format,
+        verbatim: &'a Verbatim,
+    ) -> Self {
+        Self {
+            tree,
+            dialect,
+            format,
+            rules: DialectRules::new(dialect),
+            verbatim,
+            comments: CommentMap::build(tree, verbatim),
+            printed_leading: RefCell::default(),
+            printed_trailing: RefCell::default(),
+            align_sets: Cell::new(0),
+        }
+    }
+
+    pub(crate) fn format(&self) -> Doc {
+        Doc::concat(vec![
+            self.node(&self.tree.root()),
+            leading_comments_doc(self.comments.dangling()),
+        ])
+    }
+
+    /// Lay out `node` on its own, leaving the comments before and after it to the surrounding
+    /// source.
+    pub(crate) fn format_node(&self, node: &SyntaxNode) -> Doc {
+        let element = SyntaxElement::Node(node.clone());
+        if let Some(first) = first_code_token(&element) {
+            self.printed_leading.borrow_mut().insert(first.id());
+        }
+        if let Some(last) = last_code_token(&element) {
+            self.printed_trailing.borrow_mut().insert(last.id());
+        }
+        self.node(node)
+    }
+
+    /// Lay out a child element. Comments leading a node are printed before the node's own
+    /// groups so that they do not force those groups to break.
+    fn element(&self, element: &SyntaxElement) -> Doc {
+        match element {
+            SyntaxElement::Node(node) => {
+                let leading = first_code_token(element)
+                    .map_or(Doc::Nil, |token| leading_comments_doc(self.take_leading(&token)));
+                Doc::concat(vec![leading, self.node(node)])
+            }
+            SyntaxElement::Token(token) => self.token(token),
+        }
+    }
+
+    fn node(&self, node: &SyntaxNode) -> Doc {
+        match node.kind() {
+            SyntaxKind::File => self.file(node),
+            SyntaxKind::SelectStatement | SyntaxKind::SetExpression => self.clauses(node),
+            SyntaxKind::WithCompoundStatement => self.with_compound_statement(node),
+            SyntaxKind::CommonTableExpression => self.common_table_expression(node),
+            SyntaxKind::SelectClause
+            | SyntaxKind::GroupbyClause
+            | SyntaxKind::OrderbyClause
+            | SyntaxKind::PartitionbyClause => self.list_clause(node),
+            SyntaxKind::NamedWindow => self.named_window(node),
+            SyntaxKind::WindowSpecification => self.window_specification(node),
+            SyntaxKind::WhereClause
+            | SyntaxKind::HavingClause
+            | SyntaxKind::QualifyClause
+            | SyntaxKind::LimitClause => self.clause(node),
+            SyntaxKind::FromClause => self.from(node),
+            SyntaxKind::FromExpression => self.joins(node),
+            SyntaxKind::JoinClause => self.join_clause(node),
+            SyntaxKind::Bracketed => self.bracketed(node),
+            SyntaxKind::Expression => self.expression(node),
+            SyntaxKind::CaseExpression => self.case_expression(node),
+            SyntaxKind::CreateTableStatement
+            | SyntaxKind::CreateViewStatement
+            | SyntaxKind::CreateIndexStatement => self.create_statement(node),
+            SyntaxKind::AlterTableStatement => self.alter_table(node),
+            SyntaxKind::InsertStatement => self.insert_statement(node),
+            SyntaxKind::ValuesClause => self.values_clause(node, None).1,
+            _ if self.rules.is_batch(node) => self.file(node),
+            _ if self.rules.is_angle_bracketed(node) => self.bracketed(node),
+            _ => match self.rules.clause(&SyntaxElement::Node(node.clone())) {
+                Some(ClauseBody::List) => self.list_clause(node),
+                Some(ClauseBody::Single) => self.clause(node),
+                None => self.generic(node),
+            },
+        }
+    }
+
+    fn token(&self, token: &SyntaxToken) -> Doc {
+        if !is_code(token) {
+            return Doc::Nil;
+        }
+
+        Doc::concat(vec![
+            leading_comments_doc(self.take_leading(token)),
+            if self.verbatim.is_marked_before(token) { Doc::Mark } else { Doc::Nil },
+            Doc::text(self.token_text(token)),
+            if self.verbatim.is_marked(token) { Doc::Mark } else { Doc::Nil },
+            trailing_comments_doc(self.take_trailing(token)),
+        ])
+    }
+
+    fn token_text<'t>(&self, token: &'t SyntaxToken) -> Cow<'t, str> {
+        let text = token.text();
+        match case_style(token, self.dialect, self.format) {
+            CaseStyle::Preserve => Cow::Borrowed(text),
+            CaseStyle::Upper => Cow::Owned(text.to_uppercase()),
+            CaseStyle::Lower => Cow::Owned(text.to_lowercase()),
+        }
+    }
+
+    fn take_leading(&self, token: &SyntaxToken) -> &[Comment] {
+        let first = self.printed_leading.borrow_mut().insert(token.id());
+        if first { self.comments.leading(token) } else { &[] }
+    }
+
+    fn take_trailing(&self, token: &SyntaxToken) -> &[Comment] {
+        let first = self.printed_trailing.borrow_mut().insert(token.id());
+        if first { self.comments.trailing(token) } else { &[] }
+    }
+
+    /// Take the trailing comments of `token` to print them past a neighbouring comma. Format
+    /// directives stay where they are, as moving them would move the verbatim region boundary.
+    fn take_movable_trailing(&self, token: &SyntaxToken) -> &[Comment] {
+        if self.comments.trailing(token).iter().any(|comment| comment.mark) {
+            return &[];
+        }
+        self.take_trailing(token)
+    }
+
+    /// Lay out `elements` in source order, asking `separator` what goes between the last code
+    /// token of one element and the first code token of the next.
+    fn elements_with(
+        &self,
+        elements: &[SyntaxElement],
+        separator: impl FnMut(&SyntaxToken, &SyntaxElement, &SyntaxToken) -> Doc,
+    ) -> Doc {
+        self.elements_laid_out(elements, separator, |element| self.element(element))
+    }
+
+    /// Like [`Self::elements_with`], laying out every element with `layout`.
+    fn elements_laid_out(
+        &self,
+        elements: &[SyntaxElement],
+        mut separator: impl FnMut(&SyntaxToken, &SyntaxElement, &SyntaxToken) -> Doc,
+        layout: impl Fn(&SyntaxElement) -> Doc,
+    ) -> Doc {
+        let mut parts = Vec::with_capacity(elements.len() * 2);
+        let mut previous: Option<SyntaxToken> = None;
+
+        for element in elements {
+            if let (Some(left), Some(right)) = (&previous, first_code_token(element)) {
+                parts.push(separator(left, element, &right));
+            }
+            parts.push(layout(element));
+            if let Some(last) = last_code_token(element) {
+                previous = Some(last);
+            }
+        }
+
+        Doc::concat(parts)
+    }
+
+    fn elements(&self, elements: &[SyntaxElement]) -> Doc {
+        self.elements_with(elements, |left, _, right| self.separator(left, right))
+    }
+
+    fn separator(&self, left: &SyntaxToken, right: &SyntaxToken) -> Doc {
+        match (left.kind(), right.kind()) {
+            (_, SyntaxKind::Comma | SyntaxKind::StatementTerminator | SyntaxKind::EndBracket) => {
+                Doc::Nil
+            }
+            (SyntaxKind::StartBracket | SyntaxKind::StartAngleBracket, _) => Doc::Nil,
+            (_, SyntaxKind::StartAngleBracket | SyntaxKind::EndAngleBracket) => Doc::Nil,
+            (SyntaxKind::Comma, _) => Doc::line(),
+            _ if left.text() == "=>" || right.text() == "=>" => Doc::space(),
+            _ if self.has_space_between(left, right) => Doc::space(),
+            _ => Doc::Nil,
+        }
+    }
+
+    fn has_space_between(&self, left: &SyntaxToken, right: &SyntaxToken) -> bool {
+        left.text_range().end() < right.text_range().start()
+    }
+
+    fn gap(&self, left: &SyntaxToken, right: &SyntaxToken) -> &str {
+        let start = usize::from(left.text_range().end());
+        let end = usize::from(right.text_range().start());
+        &self.tree.text()[start..end]
+    }
+
+    fn generic(&self, node: &SyntaxNode) -> Doc {
+        let children = children(node);
+        if children.iter().any(|child| token_kind(child) == Some(SyntaxKind::Comma)) {
+            Doc::group(Doc::indent(self.comma_list(&children)))
+        } else {
+            self.elements(&children)
+        }
+    }
+
+    fn file(&self, node: &SyntaxNode) -> Doc {
+        let children = children(node);
+        // Scripts that change the delimiter end statements with something other than `;`.
+        let delimited = children.iter().any(is_delimiter_command);
+        let semicolons = if delimited { SemicolonStyle::Preserve } else { self.format.semicolons };
+
+        let mut parts = Vec::with_capacity(children.len() * 2);
+        let mut previous: Option<SyntaxToken> = None;
+
+        for (index, element) in children.iter().enumerate() {
+            let Some(first) = first_code_token(element) else {
+                parts.push(self.element(element));
+                continue;
+            };
+            if let Some(left) = &previous {
+                parts.push(self.statement_separator(left, element, &first, delimited));
+            }
+            previous = last_code_token(element);
+
+            let is_terminator = first.kind() == SyntaxKind::StatementTerminator;
+            if is_terminator
+                && semicolons == SemicolonStyle::Never
+                && self.removable_terminator(&children, index)
+            {
+                parts.push(leading_comments_doc(self.take_leading(&first)));
+                parts.push(trailing_comments_doc(self.take_trailing(&first)));
+            } else if !is_terminator
+                && semicolons == SemicolonStyle::Always
+                && self.needs_terminator(&children, index)
+            {
+                let comments =
+                    previous.as_ref().map_or(&[][..], |last| self.take_movable_trailing(last));
+                parts.push(self.element(element));
+                parts.push(Doc::text(";"));
+                parts.push(trailing_comments_doc(comments));
+            } else {
+                parts.push(self.element(element));
+            }
+        }
+
+        Doc::concat(parts)
+    }
+
+    fn statement_separator(
+        &self,
+        left: &SyntaxToken,
+        right_element: &SyntaxElement,
+        right: &SyntaxToken,
+        delimited: bool,
+    ) -> Doc {
+        if delimited && right_element.as_token().is_some() {
+            // Terminators of a script with `DELIMITER` stay as written, e.g. `END $$`.
+            if self.has_space_between(left, right) { Doc::space() } else { Doc::Nil }
+        } else if right.kind() == SyntaxKind::StatementTerminator {
+            Doc::Nil
+        } else if self.rules.is_batch_separator(right_element) {
+            Doc::hard_line()
+        } else if self.rules.is_batch_separator(&SyntaxElement::Token(left.clone())) {
+            Doc::blank_lines(self.format.blank_lines_between_statements.unwrap_or(1))
+        } else if let Some(count) = self.format.blank_lines_between_statements {
+            Doc::blank_lines(count)
+        } else if has_blank_line(self.gap(left, right)) {
+            Doc::empty_line()
+        } else {
+            Doc::hard_line()
+        }
+    }
+
+    /// Whether the statement at `index` lacks the terminator `semicolons = "always"` asks for.
+    fn needs_terminator(&self, children: &[SyntaxElement], index: usize) -> bool {
+        let element = &children[index];
+        let Some(node) = element.as_node() else {
+            return false;
+        };
+        if self.rules.is_batch_separator(element) || self.rules.is_batch(node) {
+            return false;
+        }
+        // A directive after the statement marks the edge of a verbatim region, which the
+        // semicolon must not cross.
+        if last_code_token(element)
+            .is_some_and(|last| self.comments.trailing(&last).iter().any(|comment| comment.mark))
+        {
+            return false;
+        }
+        children[index + 1..]
+            .iter()
+            .find_map(first_code_token)
+            .is_none_or(|next| next.kind() != SyntaxKind::StatementTerminator)
+    }
+
+    /// Whether the terminator at `index` can be dropped for `semicolons = "never"`: always after
+    /// the last statement, and between statements in dialects that do not need it there.
+    fn removable_terminator(&self, children: &[SyntaxElement], index: usize) -> bool {
+        let Some(next) = children[index + 1..].iter().find_map(first_code_token) else {
+            return true;
+        };
+        if !self.rules.optional_terminators {
+            return false;
+        }
+        // T-SQL still needs one before a statement that starts with `WITH` and after `MERGE`.
+        let statement = children[..index].iter().rev().find_map(first_code_token);
+        !is_keyword(&SyntaxElement::Token(next), "with")
+            && !statement.is_some_and(|first| is_keyword(&SyntaxElement::Token(first), "merge"))
+    }
+
+    /// Statements made of clauses: either everything fits on one line, or every clause starts
+    /// a line of its own.
+    fn clauses(&self, node: &SyntaxNode) -> Doc {
+        let children = children(node);
+        let mut parts = Vec::new();
+        let mut has_code = false;
+
+        for clause in self.split_clauses(&children) {
+            if clause.iter().any(|element| first_code_token(element).is_some()) {
+                if has_code {
+                    parts.push(Doc::line());
+                }
+                has_code = true;
+            }
+            parts.push(match clause {
+                [keyword @ SyntaxElement::Token(_), body @ ..] => {
+                    match self.rules.clause(keyword) {
+                        Some(ClauseBody::List) => {
+                            self.headed_list(&clause[..1], self.comma_list(body))
+                        }
+                        Some(ClauseBody::Single) => self.headed(&clause[..1], body),
+                        None => self.elements(clause),
+                    }
+                }
+                _ => self.elements(clause),
+            });
+        }
+
+        Doc::group(Doc::concat(parts))
+    }
+
+    /// Every child of a statement is a clause of its own, except for clauses of the dialect
+    /// that are made of a bare keyword and the elements after it.
+    fn split_clauses<'e>(&self, children: &'e [SyntaxElement]) -> Vec<&'e [SyntaxElement]> {
+        let mut clauses = Vec::new();
+        let mut index = 0;
+
+        while index < children.len() {
+            let end = match &children[index] {
+                keyword @ SyntaxElement::Token(_) if self.rules.clause(keyword).is_some() => {
+                    children[index + 1..]
+                        .iter()
+                        .position(|element| self.rules.clause(element).is_some())
+                        .map_or(children.len(), |offset| index + 1 + offset)
+                }
+                _ => index + 1,
+            };
+            clauses.push(&children[index..end]);
+            index = end;
+        }
+
+        clauses
+    }
+
+    /// `WITH` and its common table expressions, one block each, then the query. Blank lines
+    /// between the blocks follow `blank_lines_between_ctes`.
+    fn with_compound_statement(&self, node: &SyntaxNode) -> Doc {
+        let children = children(node);
+        let first_cte = children
+            .iter()
+            .position(|element| node_kind(element) == Some(SyntaxKind::CommonTableExpression));
+        let statement = children.iter().rposition(|element| {
+            node_kind(element).is_some_and(|kind| kind != SyntaxKind::CommonTableExpression)
+        });
+
+        let (Some(first_cte), Some(statement)) = (first_cte, statement) else {
+            return self.generic(node);
+        };
+        if statement < first_cte {
+            return self.generic(node);
+        }
+
+        let ctes = &children[first_cte..statement];
+        let line = |left: &SyntaxToken, right: &SyntaxToken| {
+            let count = self
+                .format
+                .blank_lines_between_ctes
+                .unwrap_or_else(|| usize::from(has_blank_line(self.gap(left, right))));
+            Doc::blank_lines(count)
+        };
+        let query_line = match (
+            ctes.iter().rev().find_map(last_code_token),
+            children[statement..].iter().find_map(first_code_token),
+        ) {
+            (Some(left), Some(right)) => line(&left, &right),
+            _ => Doc::line(),
+        };
+
+        Doc::group(Doc::concat(vec![
+            self.elements(&children[..first_cte]),
+            Doc::space(),
+            self.comma_list_lines(
+                ctes,
+                |left, right| Some(line(left, right)),
+                |item| self.elements(item),
+            ),
+            query_line,
+            self.elements(&children[statement..]),
+        ]))
+    }
+
+    /// `name AS (query)`, with the query always on lines of its own.
+    fn common_table_expression(&self, node: &SyntaxNode) -> Doc {
+        let children = children(node);
+        let query = children.iter().rposition(|element| {
+            node_kind(element) == Some(SyntaxKind::Bracketed)
+                && element
+                    .as_node()
+                    .is_some_and(|node| node.children_with_tokens().any(|child| is_query(&child)))
+        });
+        let Some(query) = query else {
+            return self.generic(node);
+        };
+        let (before, after) = children.split_at(query);
+        let (Some(SyntaxElement::Node(bracketed)), Some(left), Some(right)) = (
+            after.first(),
+            before.iter().rev().find_map(last_code_token),
+            after.first().and_then(first_code_token),
+        ) else {
+            return self.generic(node);
+        };
+
+        Doc::concat(vec![
+            self.elements(before),
+            self.separator(&left, &right),
+            leading_comments_doc(self.take_leading(&right)),
+            self.bracketed_with(bracketed, true, |item| self.elements(item)),
+        ])
+    }
+
+    /// `WINDOW w AS (...), ...`, laid out like the common table expressions of `WITH`.
+    fn named_window(&self, node: &SyntaxNode) -> Doc {
+        let children = children(node);
+        let (head, body) = split_clause_head(&children, false);
+        if !body.iter().any(|element| first_code_token(element).is_some()) {
+            return self.elements(&children);
+        }
+
+        Doc::group(Doc::concat(vec![self.elements(head), Doc::space(), self.comma_list(body)]))
+    }
+
+    /// The `PARTITION BY`, `ORDER BY` and frame of a window. They break along with the brackets
+    /// around them, one per line, so that a window either fits on one line or has a line for
+    /// each of them.
+    fn window_specification(&self, node: &SyntaxNode) -> Doc {
+        self.elements_with(&children(node), |_, _, _| Doc::line())
+    }
+
+    /// Clauses whose body is a comma separated list, such as `SELECT` and `ORDER BY`.
+    fn list_clause(&self, node: &SyntaxNode) -> Doc {
+        let children = children(node);
+        let (head, body) = split_clause_head(&children, self.rules.select_top);
+        if !body.iter().any(|element| first_code_token(element).is_some()) {
+            return self.elements(&children);
+        }
+
+        let list = match self.align_set() {
+            Some(set) if node.kind() == SyntaxKind::SelectClause => {
+                self.comma_list_with(body, |item| {
+                    let mut code =
+                        item.iter().filter(|element| first_code_token(element).is_some());
+                    match (code.next(), code.next()) {
+                        (Some(element), None) => self.aligned(element, set, |children| {
+                            children.iter().position(|child| {
+                                node_kind(child) == Some(SyntaxKind::AliasExpression)
+                            })
+                        }),
+                        _ => self.elements(item),
+                    }
+                })
+            }
+            _ => self.comma_list(body),
+        };
+
+        self.headed_list(head, list)
+    }
+
+    fn headed_list(&self, head: &[SyntaxElement], list: Doc) -> Doc {
+        Doc::group(Doc::concat(vec![
+            self.elements(head),
+            Doc::indent(Doc::concat(vec![Doc::line(), list])),
+        ]))
+    }
+
+    /// Clauses with a single body, such as `WHERE`, whose continuation lines are indented.
+    fn clause(&self, node: &SyntaxNode) -> Doc {
+        let children = children(node);
+        let (head, body) = split_clause_head(&children, self.rules.select_top);
+        if head.is_empty() || !body.iter().any(|element| first_code_token(element).is_some()) {
+            return self.elements(&children);
+        }
+
+        self.headed(head, body)
+    }
+
+    fn headed(&self, head: &[SyntaxElement], body: &[SyntaxElement]) -> Doc {
+        Doc::concat(vec![self.elements(head), Doc::space(), Doc::indent(self.elements(body))])
+    }
+
+    fn from(&self, node: &SyntaxNode) -> Doc {
+        let children = children(node);
+        let (head, body) = split_clause_head(&children, self.rules.select_top);
+        if head.is_empty() || !body.iter().any(|element| first_code_token(element).is_some()) {
+            return self.elements(&children);
+        }
+
+        if body.iter().any(|element| token_kind(element) == Some(SyntaxKind::Comma)) {
+            return self.list_clause(node);
+        }
+
+        Doc::concat(vec![self.elements(head), Doc::space(), self.elements(body)])
+    }
+
+    /// Joins start a line of their own, aligned with the `FROM` keyword.
+    fn joins(&self, node: &SyntaxNode) -> Doc {
+        let children = children(node);
+        self.elements_with(&children, |left, right_element, right| {
+            if node_kind(right_element) == Some(SyntaxKind::JoinClause) {
+                Doc::hard_line()
+            } else {
+                self.separator(left, right)
+            }
+        })
+    }
+
+    fn join_clause(&self, node: &SyntaxNode) -> Doc {
+        let children = children(node);
+        let condition = children.iter().position(|element| {
+            node_kind(element) == Some(SyntaxKind::JoinOnCondition)
+                || is_keyword(element, "on")
+                || is_keyword(element, "using")
+        });
+
+        match condition {
+            Some(index) if index > 0 => Doc::group(Doc::concat(vec![
+                self.elements(&children[..index]),
+                Doc::indent(Doc::concat(vec![Doc::line(), self.elements(&children[index..])])),
+            ])),
+            _ => self.elements(&children),
+        }
+    }
+
+    fn bracketed(&self, node: &SyntaxNode) -> Doc {
+        if self.rules.hugs_call(node) {
+            return self.elements(&children(node));
+        }
+        self.bracketed_with(node, false, |item| self.elements(item))
+    }
+
+    /// Lay out a bracketed list, laying out every item with `layout`. A `broken` list always has
+    /// one item per line.
+    fn bracketed_with(
+        &self,
+        node: &SyntaxNode,
+        broken: bool,
+        layout: impl Fn(&[SyntaxElement]) -> Doc,
+    ) -> Doc {
+        let children = children(node);
+        let Some((open, close)) = brackets(&children) else {
+            return self.generic(node);
+        };
+        let inner = &children[open + 1..close];
+        if !inner.iter().any(|element| first_code_token(element).is_some()) {
+            return self.elements(&children);
+        }
+
+        let doc = Doc::concat(vec![
+            self.elements(&children[..=open]),
+            Doc::indent(Doc::concat(vec![Doc::soft_line(), self.comma_list_with(inner, layout)])),
+            Doc::soft_line(),
+            self.elements(&children[close..]),
+        ]);
+        let doc = if broken { Doc::broken_group(doc) } else { Doc::group(doc) };
+        // Subqueries indent from the line of their opening bracket, wherever the expression
+        // around them would have its continuation lines.
+        if inner.iter().any(is_query) { Doc::anchored(doc) } else { doc }
+    }
+
+    /// `CREATE TABLE`, `CREATE VIEW` and `CREATE INDEX`: the name and column list first, then
+    /// every option and the query. Options of tables and views go on lines of their own; those
+    /// of indexes only break when the statement does not fit.
+    fn create_statement(&self, node: &SyntaxNode) -> Doc {
+        let children = children(node);
+        let Some(name) = children.iter().position(|element| {
+            matches!(
+                node_kind(element),
+                Some(SyntaxKind::TableReference | SyntaxKind::ObjectReference)
+            )
+        }) else {
+            return self.generic(node);
+        };
+
+        let mut sections = Vec::new();
+        let mut start = 0;
+        for (index, element) in children.iter().enumerate().skip(name + 1) {
+            if is_query(element) || is_table_option(element) {
+                sections.push(&children[start..index]);
+                start = index;
+            }
+        }
+        sections.push(&children[start..]);
+
+        let mut parts = Vec::with_capacity(sections.len() * 2);
+        for (index, section) in sections.into_iter().enumerate() {
+            if index == 0 {
+                parts.push(self.elements_laid_out(
+                    section,
+                    |left, _, right| self.separator(left, right),
+                    |element| self.column_list(element),
+                ));
+                continue;
+            }
+            if node.kind() == SyntaxKind::CreateIndexStatement {
+                parts.push(Doc::line());
+            } else {
+                parts.push(Doc::hard_line());
+            }
+            match section {
+                [query] if is_query(query) => parts.push(self.element(query)),
+                _ => parts.push(Doc::group(Doc::indent(self.elements(section)))),
+            }
+        }
+
+        Doc::group(Doc::concat(parts))
+    }
+
+    /// A column list of `CREATE TABLE` with one column definition per line, with the names,
+    /// types and constraints lined up in columns when alignment is enabled.
+    fn column_list(&self, element: &SyntaxElement) -> Doc {
+        let SyntaxElement::Node(node) = element else {
+            return self.element(element);
+        };
+        let is_column_list = node.kind() == SyntaxKind::Bracketed
+            && node.children().any(|child| child.kind() == SyntaxKind::ColumnDefinition);
+        if !is_column_list {
+            return self.element(element);
+        }
+
+        let leading = first_code_token(element)
+            .map_or(Doc::Nil, |token| leading_comments_doc(self.take_leading(&token)));
+        let sets = self.align_set().zip(self.align_set());
+        let columns = self.bracketed_with(node, true, |item| match sets {
+            Some((names, types)) => self.column_definition(item, names, types),
+            None => self.elements(item),
+        });
+        Doc::concat(vec![leading, columns])
+    }
+
+    fn column_definition(&self, item: &[SyntaxElement], names: usize, types: usize) -> Doc {
+        let mut code = item.iter().filter(|element| first_code_token(element).is_some());
+        let (Some(element @ SyntaxElement::Node(node)), None) = (code.next(), code.next()) else {
+            return self.elements(item);
+        };
+        let children = children(node);
+        let data_type = children
+            .iter()
+            .position(|child| node_kind(child) == Some(SyntaxKind::DataType))
+            .filter(|&index| index > 0);
+        let Some(data_type) = data_type.filter(|_| node.kind() == SyntaxKind::ColumnDefinition)
+        else {
+            return self.elements(item);
+        };
+
+        let leading = first_code_token(element)
+            .map_or(Doc::Nil, |token| leading_comments_doc(self.take_leading(&token)));
+        let (name, rest) = children.split_at(data_type);
+        let constraints = &rest[1..];
+        let typed = if constraints.iter().any(|child| first_code_token(child).is_some()) {
+            Doc::concat(vec![
+                Doc::align(types, self.element(&rest[0])),
+                Doc::space(),
+                self.elements(constraints),
+            ])
+        } else {
+            self.elements(rest)
+        };
+
+        Doc::concat(vec![leading, Doc::align(names, self.elements(name)), Doc::space(), typed])
+    }
+
+    /// `ALTER TABLE` with several actions puts one action per line when they do not fit.
+    fn alter_table(&self, node: &SyntaxNode) -> Doc {
+        let children = children(node);
+        let name = children
+            .iter()
+            .position(|element| node_kind(element) == Some(SyntaxKind::TableReference));
+        let Some(name) = name else {
+            return self.generic(node);
+        };
+
+        let (head, body) = children.split_at(name + 1);
+        if !body.iter().any(|element| token_kind(element) == Some(SyntaxKind::Comma)) {
+            return self.elements(&children);
+        }
+        self.headed_list(head, self.comma_list(body))
+    }
+
+    /// `INSERT`, breaking before its rows or query when it does not fit. When `VALUES` rows are
+    /// lined up, the column list goes on a line of its own and lines up with them.
+    fn insert_statement(&self, node: &SyntaxNode) -> Doc {
+        let children = children(node);
+        let Some(split) = children.iter().position(|element| {
+            node_kind(element) == Some(SyntaxKind::ValuesClause) || is_query(element)
+        }) else {
+            return self.generic(node);
+        };
+        let (head, body) = children.split_at(split);
+
+        // Only a column list right before the rows can join them.
+        let columns = head
+            .iter()
+            .rposition(|element| first_code_token(element).is_some())
+            .filter(|&index| node_kind(&head[index]) == Some(SyntaxKind::Bracketed));
+        let (header, first) = match body[0].as_node() {
+            Some(values) if values.kind() == SyntaxKind::ValuesClause => {
+                self.values_clause(values, columns.and_then(|index| head[index].as_node()))
+            }
+            _ => (None, self.element(&body[0])),
+        };
+        let rest = if body[1..].iter().any(|element| first_code_token(element).is_some()) {
+            Doc::concat(vec![Doc::line(), self.elements_with(&body[1..], |_, _, _| Doc::line())])
+        } else {
+            self.elements(&body[1..])
+        };
+
+        match (header, columns) {
+            (Some(header), Some(columns)) => Doc::concat(vec![
+                self.elements(&head[..columns]),
+                Doc::indent(Doc::concat(vec![Doc::hard_line(), header])),
+                self.elements(&head[columns + 1..]),
+                Doc::hard_line(),
+                first,
+                rest,
+            ]),
+            _ => Doc::group(Doc::concat(vec![self.elements(head), Doc::line(), first, rest])),
+        }
+    }
+
+    /// `VALUES` and its rows, one per line when there are several. With `align`, the items of
+    /// the rows line up like a table, along with the `columns` of the `INSERT` around them, which
+    /// are returned laid out when they are part of the table. Lists of more than
+    /// `max_values_rows` rows are kept as written.
+    fn values_clause(&self, node: &SyntaxNode, columns: Option<&SyntaxNode>) -> (Option<Doc>, Doc) {
+        let children = children(node);
+        let (head, body) = split_clause_head(&children, false);
+        let rows: Vec<&SyntaxNode> = body
+            .iter()
+            .filter_map(|element| {
+                element.as_node().filter(|node| node.kind() == SyntaxKind::Bracketed)
+            })
+            .collect();
+
+        if rows.len() > self.format.max_values_rows {
+            return (None, self.as_written(node));
+        }
+        if rows.len() < 2 {
+            if head.is_empty() || !body.iter().any(|element| first_code_token(element).is_some()) {
+                return (None, self.elements(&children));
+            }
+            return (None, self.headed_list(head, self.comma_list(body)));
+        }
+
+        let tabular = self.format.align
+            && body.iter().all(|element| {
+                first_code_token(element).is_none()
+                    || matches!(token_kind(element), Some(SyntaxKind::Comma))
+                    || node_kind(element) == Some(SyntaxKind::Bracketed)
+            });
+        let (header, list) = if tabular {
+            let table: Vec<&SyntaxNode> = columns.into_iter().chain(rows).collect();
+            let cells: Vec<Vec<Doc>> = table.iter().map(|row| self.row_cells(row)).collect();
+            let widths: Vec<Vec<Option<usize>>> =
+                cells.iter().map(|row| row.iter().map(Doc::flat_width).collect()).collect();
+            // Cells start after the bracket, and rows after the first also after a leading comma.
+            let first_row = usize::from(columns.is_some());
+            let leading = self.format.comma_style == CommaStyle::Leading;
+            let offsets: Vec<usize> = (0..table.len())
+                .map(|index| if leading && index > first_row { 3 } else { 1 })
+                .collect();
+            let padding = table::column_padding(&widths, &offsets, self.format.max_align_padding);
+
+            let mut rows = table.into_iter().zip(cells.into_iter().zip(padding));
+            let header = match columns {
+                Some(_) => {
+                    rows.next().map(|(row, (cells, padding))| self.table_row(row, cells, &padding))
+                }
+                None => None,
+            };
+            let rows = RefCell::new(rows);
+            let list = self.comma_list_with(body, |item| match rows.borrow_mut().next() {
+                Some((row, (cells, padding))) => self.table_row(row, cells, &padding),
+                None => self.elements(item),
+            });
+            (header, list)
+        } else {
+            (None, self.comma_list(body))
+        };
+
+        let clause = Doc::broken_group(Doc::concat(vec![
+            self.elements(head),
+            Doc::indent(Doc::concat(vec![Doc::line(), list])),
+        ]));
+        (header, clause)
+    }
+
+    /// The items of a bracketed row, each laid out with the comma after it.
+    fn row_cells(&self, row: &SyntaxNode) -> Vec<Doc> {
+        let children = children(row);
+        let Some((open, close)) = brackets(&children) else {
+            return Vec::new();
+        };
+
+        let mut cells = Vec::new();
+        let mut item = Vec::new();
+        for element in &children[open + 1..close] {
+            if token_kind(element) == Some(SyntaxKind::Comma) {
+                cells.push(Doc::concat(vec![self.elements(&item), self.element(element)]));
+                item.clear();
+            } else {
+                item.push(element.clone());
+            }
+        }
+        cells.push(self.elements(&item));
+        cells
+    }
+
+    /// A row of a table, with `padding` after each of its `cells`. Rows whose cells do not all
+    /// fit on one line break like other bracketed lists instead.
+    fn table_row(&self, row: &SyntaxNode, cells: Vec<Doc>, padding: &[usize]) -> Doc {
+        let children = children(row);
+        let Some((open, close)) = brackets(&children) else {
+            return self.elements(&children);
+        };
+        let open = self.elements(&children[..=open]);
+        let close = self.elements(&children[close..]);
+
+        if cells.iter().any(|cell| cell.flat_width().is_none()) {
+            let mut items = Vec::with_capacity(cells.len() * 2);
+            for (index, cell) in cells.into_iter().enumerate() {
+                if index > 0 {
+                    items.push(Doc::line());
+                }
+                items.push(cell);
+            }
+            return Doc::group(Doc::concat(vec![
+                open,
+                Doc::indent(Doc::concat(vec![Doc::soft_line(), Doc::concat(items)])),
+                Doc::soft_line(),
+                close,
+            ]));
+        }
+
+        let mut parts = vec![open];
+        for (index, cell) in cells.into_iter().enumerate() {
+            if index > 0 {
+                let width = padding.get(index - 1).copied().unwrap_or(0);
+                parts.push(Doc::text(" ".repeat(width + 1)));
+            }
+            parts.push(cell);
+        }
+        parts.push(close);
+        Doc::concat(parts)
+    }
+
+    /// `node` exactly as written, from its first to its last code token.
+    fn as_written(&self, node: &SyntaxNode) -> Doc {
+        let element = SyntaxElement::Node(node.clone());
+        let (Some(first), Some(last)) = (first_code_token(&element), last_code_token(&element))
+        else {
+            return self.elements(&children(node));
+        };
+
+        // The comments in between are part of the text.
+        for token in node.descendants_with_tokens().filter_map(SyntaxElement::into_token) {
+            if token.id() != first.id() {
+                self.take_leading(&token);
+            }
+            if token.id() != last.id() {
+                self.take_trailing(&token);
+            }
+        }
+
+        let text =
+            &self.tree.text()[TextRange::new(first.text_range().start(), last.text_range().end())];
+        Doc::concat(vec![
+            leading_comments_doc(self.take_leading(&first)),
+            Doc::text(text),
+            trailing_comments_doc(self.take_trailing(&last)),
+        ])
+    }
+
+    /// Boolean chains break at each `AND` / `OR` once they no longer fit.
+    fn expression(&self, node: &SyntaxNode) -> Doc {
+        let children = children(node);
+        let operands = boolean_operands(&children);
+        let set = if operands.len() > 1 { self.align_set() } else { None };
+
+        let (first, rest) = self.operator_chain(&operands, |operand| {
+            let comparison = operand
+                .iter()
+                .position(|element| node_kind(element) == Some(SyntaxKind::ComparisonOperator));
+            match comparison {
+                Some(split) => self.comparison(operand, split, set),
+                None => self.chain(operand),
+            }
+        });
+        Doc::group(Doc::concat(vec![first, rest]))
+    }
+
+    /// A comparison whose operator sits at `index`, lined up with the other comparisons of
+    /// `set` when there is one.
+    fn comparison(&self, elements: &[SyntaxElement], index: usize, set: Option<usize>) -> Doc {
+        let (before, after) = elements.split_at(index);
+        let left = before.iter().rev().find_map(last_code_token);
+        let right = after.iter().find_map(first_code_token);
+        let (Some(left), Some(right)) = (left, right) else {
+            return self.chain(elements);
+        };
+
+        let before = self.chain(before);
+        Doc::concat(vec![
+            match set {
+                Some(set) => Doc::align(set, before),
+                None => before,
+            },
+            self.separator(&left, &right),
+            self.chain(after),
+        ])
+    }
+
+    /// Arithmetic and `||` chains break once they no longer fit, continuing one level deeper.
+    fn chain(&self, elements: &[SyntaxElement]) -> Doc {
+        let operands = chain_operands(elements);
+        if operands.len() < 2 {
+            return self.elements(elements);
+        }
+        let (first, rest) = self.operator_chain(&operands, |operand| self.elements(operand));
+        Doc::group(Doc::concat(vec![first, Doc::indent(rest)]))
+    }
+
+    /// Lay out the first operand of a chain and, apart, the line breaks and the rest of the
+    /// operands, which each start with their operator. With trailing operators, every operator
+    /// moves to the end of the line before it, ahead of the comments trailing that line.
+    fn operator_chain(
+        &self,
+        operands: &[&[SyntaxElement]],
+        layout: impl Fn(&[SyntaxElement]) -> Doc,
+    ) -> (Doc, Doc) {
+        let trailing = self.format.operator_style == OperatorStyle::Trailing;
+        let mut first = Doc::Nil;
+        let mut parts = Vec::with_capacity(operands.len() * 5);
+        let mut previous: Option<SyntaxToken> = None;
+        let mut moved: &[Comment] = &[];
+
+        for (index, operand) in operands.iter().enumerate() {
+            let mut operand = *operand;
+            if index > 0
+                && trailing
+                && let Some((operator, rest)) = operand.split_first()
+            {
+                if let (Some(left), Some(right)) = (&previous, first_code_token(operator)) {
+                    parts.push(self.separator(left, &right));
+                }
+                parts.push(self.element(operator));
+                parts.push(trailing_comments_doc(moved));
+                operand = rest;
+            }
+
+            let last = operand.iter().rev().find_map(last_code_token);
+            moved = match &last {
+                Some(last) if trailing && index + 1 < operands.len() => {
+                    self.take_movable_trailing(last)
+                }
+                _ => &[],
+            };
+            if index == 0 {
+                first = layout(operand);
+            } else {
+                parts.push(Doc::line());
+                parts.push(layout(operand));
+            }
+            previous = last.or(previous);
+        }
+
+        (first, Doc::concat(parts))
+    }
+
+    fn case_expression(&self, node: &SyntaxNode) -> Doc {
+        let children = children(node);
+        let is_arm = |element: &SyntaxElement| {
+            matches!(node_kind(element), Some(SyntaxKind::WhenClause | SyntaxKind::ElseClause))
+        };
+        let (Some(first), Some(last)) =
+            (children.iter().position(is_arm), children.iter().rposition(is_arm))
+        else {
+            return self.generic(node);
+        };
+
+        let arms = &children[first..=last];
+        let arms = match self.align_set() {
+            Some(set) => self.elements_laid_out(
+                arms,
+                |_, _, _| Doc::line(),
+                |arm| {
+                    self.aligned(arm, set, |children| {
+                        children.iter().position(|child| is_keyword(child, "then"))
+                    })
+                },
+            ),
+            None => self.elements_with(arms, |_, _, _| Doc::line()),
+        };
+
+        Doc::group(Doc::concat(vec![
+            self.elements(&children[..first]),
+            Doc::indent(Doc::concat(vec![Doc::line(), arms])),
+            Doc::line(),
+            self.elements(&children[last + 1..]),
+        ]))
+    }
+
+    /// Lay out comma separated items, breaking between every item when they do not fit.
+    ///
+    /// With trailing commas, comments trailing an item are moved after its comma so that the
+    /// comma stays on the item's line. With leading commas, comments trailing a comma are moved
+    /// before the line break instead.
+    fn comma_list(&self, elements: &[SyntaxElement]) -> Doc {
+        self.comma_list_with(elements, |item| self.elements(item))
+    }
+
+    /// Like [`Self::comma_list`], laying out every item with `layout`.
+    fn comma_list_with(
+        &self,
+        elements: &[SyntaxElement],
+        layout: impl Fn(&[SyntaxElement]) -> Doc,
+    ) -> Doc {
+        self.comma_list_lines(elements, |_, _| None, layout)
+    }
+
+    /// Like [`Self::comma_list_with`], breaking between two items with the line `line` returns
+    /// for the last code token of the first and the first code token of the second, if any.
+    fn comma_list_lines(
+        &self,
+        elements: &[SyntaxElement],
+        line: impl Fn(&SyntaxToken, &SyntaxToken) -> Option<Doc>,
+        layout: impl Fn(&[SyntaxElement]) -> Doc,
+    ) -> Doc {
+        let mut parts = Vec::new();
+        let mut item = Vec::new();
+
+        for (index, element) in elements.iter().enumerate() {
+            match element {
+                SyntaxElement::Token(token) if token.kind() == SyntaxKind::Comma => {
+                    let last = item.iter().rev().find_map(last_code_token);
+                    let next = elements[index + 1..].iter().find_map(first_code_token);
+                    let line = match (&last, &next) {
+                        (Some(last), Some(next)) => line(last, next),
+                        _ => None,
+                    };
+                    match self.format.comma_style {
+                        CommaStyle::Trailing => {
+                            let moved =
+                                last.map_or(&[][..], |last| self.take_movable_trailing(&last));
+                            parts.push(layout(&item));
+                            parts.push(self.token(token));
+                            parts.push(trailing_comments_doc(moved));
+                            parts.push(line.unwrap_or_else(Doc::line));
+                        }
+                        CommaStyle::Leading => {
+                            let moved = self.take_movable_trailing(token);
+                            parts.push(layout(&item));
+                            parts.push(trailing_comments_doc(moved));
+                            parts.push(line.unwrap_or_else(Doc::soft_line));
+                            parts.push(self.token(token));
+                            parts.push(Doc::space());
+                        }
+                    }
+                    item.clear();
+                }
+                _ => item.push(element.clone()),
+            }
+        }
+        parts.push(layout(&item));
+
+        Doc::concat(parts)
+    }
+
+    /// A new set of columns to line up, when alignment is enabled.
+    fn align_set(&self) -> Option<usize> {
+        if !self.format.align {
+            return None;
+        }
+        let set = self.align_sets.get();
+        self.align_sets.set(set + 1);
+        Some(set)
+    }
+
+    /// Lay out `element`, padding its children before the one `split` finds so that the rest
+    /// lines up with the other elements of `set`.
+    fn aligned(
+        &self,
+        element: &SyntaxElement,
+        set: usize,
+        split: impl Fn(&[SyntaxElement]) -> Option<usize>,
+    ) -> Doc {
+        let SyntaxElement::Node(node) = element else {
+            return self.element(element);
+        };
+        let children = children(node);
+        let Some(index) = split(&children) else {
+            return self.element(element);
+        };
+
+        let leading = first_code_token(element)
+            .map_or(Doc::Nil, |token| leading_comments_doc(self.take_leading(&token)));
+        Doc::concat(vec![leading, self.split_aligned(set, &children, index)])
+    }
+
+    fn split_aligned(&self, set: usize, elements: &[SyntaxElement], index: usize) -> Doc {
+        let (before, after) = elements.split_at(index);
+        let left = before.iter().rev().find_map(last_code_token);
+        let right = after.iter().find_map(first_code_token);
+        let (Some(left), Some(right)) = (left, right) else {
+            return self.elements(elements);
+        };
+
+        Doc::concat(vec![
+            Doc::align(set, self.elements(before)),
+            self.separator(&left, &right),
+            self.elements(after),
+        ])
+    }
+}
+
+fn children(node: &SyntaxNode) -> Vec<SyntaxElement> {
+    node.children_with_tokens().collect()
+}
+
+/// Split a clause into its leading keywords (`GROUP BY`, `SELECT DISTINCT`) and its body. With
+/// `select_top`, the row count of `SELECT TOP n` is part of the head.
+fn split_clause_head(
+    children: &[SyntaxElement],
+    select_top: bool,
+) -> (&[SyntaxElement], &[SyntaxElement]) {
+    let mut after_top = false;
+    let index = children
+        .iter()
+        .position(|element| {
+            if first_code_token(element).is_none() {
+                return false;
+            }
+            if after_top {
+                after_top = false;
+                return false;
+            }
+            after_top = select_top && is_keyword(element, "top");
+            match element {
+                SyntaxElement::Token(token) => token.kind() != SyntaxKind::Keyword,
+                SyntaxElement::Node(node) => node.kind() != SyntaxKind::SelectClauseModifier,
+            }
+        })
+        .unwrap_or(children.len());
+    children.split_at(index)
+}
+
+fn is_query(element: &SyntaxElement) -> bool {
+    matches!(
+        node_kind(element),
+        Some(
+            SyntaxKind::SelectStatement
+                | SyntaxKind::SetExpression
+                | SyntaxKind::WithCompoundStatement
+        )
+    )
+}
+
+/// Options that follow the name and column list of `CREATE` statements, such as
+/// `PARTITION BY`, `CLUSTER BY` and `WITH (...)`.
+fn is_table_option(element: &SyntaxElement) -> bool {
+    const OPTIONS: &[&str] = &[
+        "cluster",
+        "clustered",
+        "comment",
+        "diststyle",
+        "distkey",
+        "engine",
+        "include",
+        "inherits",
+        "location",
+        "options",
+        "order",
+        "partition",
+        "partitioned",
+        "primary",
+        "settings",
+        "sortkey",
+        "stored",
+        "tablespace",
+        "tblproperties",
+        "ttl",
+        "using",
+        "where",
+        "with",
+    ];
+    first_code_token(element).is_some_and(|token| {
+        token.kind() == SyntaxKind::Keyword
+            && OPTIONS.iter().any(|option| token.text().eq_ignore_ascii_case(option))
+    })
+}
+
+/// The positions of the opening and closing bracket among the children of a bracketed node.
+fn brackets(children: &[SyntaxElement]) -> Option<(usize, usize)> {
+    let open = children.iter().position(|element| {
+        matches!(
+            token_kind(element),
+            Some(SyntaxKind::StartBracket | SyntaxKind::StartAngleBracket)
+        )
+    })?;
+    let close = children.iter().rposition(|element| {
+        matches!(token_kind(element), Some(SyntaxKind::EndBracket | SyntaxKind::EndAngleBracket))
+    })?;
+    (open < close).then_some((open, close))
+}
+
+/// Split a boolean chain before every `AND` / `OR`, leaving the `AND` of `BETWEEN` alone.
+fn boolean_operands(children: &[SyntaxElement]) -> Vec<&[SyntaxElement]> {
+    let mut operands = Vec::new();
+    let mut start = 0;
+    let mut in_between = false;
+
+    for (index, element) in children.iter().enumerate() {
+        if is_keyword(element, "between") {
+            in_between = true;
+        }
+        let SyntaxElement::Token(token) = element else { continue };
+        if !is_boolean_operator(token) {
+            continue;
+        }
+        if in_between && token.text().eq_ignore_ascii_case("and") {
+            in_between = false;
+        } else if index > start {
+            operands.push(&children[start..index]);
+            start = index;
+        }
+    }
+
+    operands.push(&children[start..]);
+    operands
+}
+
+/// Split an arithmetic or `||` chain before every operator of the loosest precedence in it.
+fn chain_operands(children: &[SyntaxElement]) -> Vec<&[SyntaxElement]> {
+    let operators: Vec<(usize, u8)> = children
+        .iter()
+        .enumerate()
+        .skip_while(|(_, element)| first_code_token(element).is_none())
+        .skip(1)
+        .filter_map(|(index, element)| Some((index, chain_precedence(element)?)))
+        .collect();
+    let Some(loosest) = operators.iter().map(|&(_, precedence)| precedence).min() else {
+        return vec![children];
+    };
+
+    let mut operands = Vec::new();
+    let mut start = 0;
+    for (index, precedence) in operators {
+        if precedence == loosest {
+            operands.push(&children[start..index]);
+            start = index;
+        }
+    }
+    operands.push(&children[start..]);
+    operands
+}
+
+/// The precedence of `element` if it is an arithmetic, bitwise or concatenation operator: `0`
+/// for the loosest, such as `+` and `||`, and `1` for `*`, `/` and `%`.
+fn chain_precedence(element: &SyntaxElement) -> Option<u8> {
+    let text = match element {
+        SyntaxElement::Token(token) if token.kind() == SyntaxKind::BinaryOperator => {
+            Cow::Borrowed(token.text())
+        }
+        SyntaxElement::Node(node) if node.kind() == SyntaxKind::BinaryOperator => Cow::Owned(
+            node.descendants_with_tokens()
+                .filter_map(|child| {
+                    child.into_token().filter(is_code).map(|token| token.text().to_string())
+                })
+                .collect::<String>(),
+        ),
+        _ => return None,
+    };
+    match text.as_ref() {
+        "*" | "/" | "%" | "//" => Some(1),
+        text if text.bytes().any(|byte| byte.is_ascii_alphabetic()) => None,
+        _ => Some(0),
+    }
+}
+
+fn is_boolean_operator(token: &SyntaxToken) -> bool {
+    matches!(token.kind(), SyntaxKind::BinaryOperator | SyntaxKind::Keyword)
+        && (token.text().eq_ignore_ascii_case("and") || token.text().eq_ignore_ascii_case("or"))
+}
+
+/// Whether the line following the previous token is empty. Blank lines further down the gap
+/// belong to the comments in between, which keep track of them on their own.
+fn has_blank_line(gap: &str) -> bool {
+    let mut lines = gap.split('\n').skip(1);
+    matches!((lines.next(), lines.next()), (Some(line), Some(_)) if line.trim().is_empty())
+}
@@ -1,5 +1,76 @@
-use tidysql_syntax::DialectKind;
+use std::fmt;
 
-pub fn format_with_dialect(source: &str, _dialect: DialectKind) -> String {
-    source.to_string()
+use tidysql_config::Format;
+use tidysql_syntax::{DialectKind, ParseError, SyntaxTree, TextRange};
+
+mod comments;
+mod dialect;
+mod directives;
+mod doc;
+mod layout;
+mod printer;
+mod range;
+mod table;
+mod tokens;
+mod verify;
+
+pub use range::format_range;
+pub use verify::VerifyError;
+
+#[derive(Debug)]
+pub enum FormatError {
+    Parse(ParseError),
+    Verify(VerifyError),
+}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FormatError::Parse(error) => write!(f, "{error}"),
+            FormatError::Verify(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl std::error::Error for FormatError {}
+
+pub fn format_with_dialect(
+    source: &str,
+    dialect: DialectKind,
+    format: &Format,
+) -> Result<String, FormatError> {
+    let tree = tidysql_syntax::parse(source, dialect).map_err(FormatError::Parse)?;
+    format_verified(&tree, dialect, format)
+}
+
+fn format_verified(
+    tree: &SyntaxTree,
+    dialect: DialectKind,
+    format: &Format,
+) -> Result<String, FormatError> {
+    let formatted = format_tree(tree, dialect, format);
+
+    if format.verify {
+        verify::verify(tree, &formatted, dialect, format).map_err(FormatError::Verify)?;
+    }
+
+    Ok(formatted)
+}
+
+pub fn format_tree(tree: &SyntaxTree, dialect: DialectKind, format: &Format) -> String {
+    let verbatim = directives::Verbatim::build(tree);
+    let doc = layout::Formatter::new(tree, dialect, format, &verbatim).format();
+    let (printed, marks) = printer::print_marked(&doc, &printer::PrintOptions::new(format, ""));
+
+    let mut output = verbatim.splice(tree.text(), printed, &marks);
+    if !output.is_empty() && verbatim.unclosed.is_none() {
+        output.push('\n');
+    }
+    output
+}
+
+/// The `-- tidysql: format off` comment that is never followed by `-- tidysql: format on`, if
+/// any. Everything after it is left as is by the formatter.
+pub fn unclosed_format_off(tree: &SyntaxTree) -> Option<TextRange> {
+    directives::Verbatim::build(tree).unclosed
 }
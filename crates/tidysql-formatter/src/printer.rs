@@ -0,0 +1,406 @@
+use tidysql_config::{Format, IndentStyle};
+
+use crate::doc::{Doc, LineKind};
+
+pub(crate) struct PrintOptions<'a> {
+    pub(crate) line_width: usize,
+    pub(crate) indent_width: usize,
+    pub(crate) use_tabs: bool,
+    pub(crate) max_align_padding: usize,
+    /// Written at the start of every line after the first, ahead of its own indentation.
+    pub(crate) line_prefix: &'a str,
+}
+
+impl<'a> PrintOptions<'a> {
+    /// Options for printing with `format` at the end of `line_prefix`, the indentation of the
+    /// line the output starts on. The prefix is repeated on every line break the printer emits
+    /// and its width is taken off the line width; line breaks inside tokens are left alone.
+    pub(crate) fn new(format: &Format, line_prefix: &'a str) -> Self {
+        let prefix_width: usize = line_prefix
+            .chars()
+            .map(|character| if character == '\t' { format.indent_width } else { 1 })
+            .sum();
+        Self {
+            line_width: format.max_line_width.saturating_sub(prefix_width),
+            indent_width: format.indent_width,
+            use_tabs: format.indent_style == IndentStyle::Tabs,
+            max_align_padding: format.max_align_padding,
+            line_prefix,
+        }
+    }
+}
+
+/// Printing passes made to settle the padding of nested [`Doc::Align`] sets.
+const MAX_ALIGN_PASSES: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Flat,
+    Break,
+}
+
+#[derive(Clone, Copy)]
+struct Command<'a> {
+    indent: usize,
+    mode: Mode,
+    doc: &'a Doc,
+}
+
+/// Render `doc` as text, choosing for each group whether it fits on the current line.
+///
+/// Newlines are buffered until the next text is written so that consecutive line breaks
+/// collapse into one (or into a single blank line), and trailing whitespace is never emitted.
+pub(crate) fn print(doc: &Doc, options: &PrintOptions) -> String {
+    print_marked(doc, options).0
+}
+
+/// Like [`print`], also returning the output offset of every [`Doc::Mark`] in order.
+///
+/// Columns of [`Doc::Align`] sets are only known once the line breaks are: the document is
+/// printed once to find where every set ends up, and again with the padding in place. A set
+/// that follows another one on the same lines, such as data types after column names, moves
+/// with the padding of the first, so printing repeats until the padding settles.
+pub(crate) fn print_marked(doc: &Doc, options: &PrintOptions) -> (String, Vec<usize>) {
+    let mut printer = Printer::new(options, Vec::new());
+    printer.run(doc);
+
+    for _ in 0..MAX_ALIGN_PASSES {
+        let padding = padding(&printer, options);
+        let settled = padding
+            .iter()
+            .enumerate()
+            .all(|(index, &width)| printer.padding.get(index).copied().unwrap_or(0) == width);
+        if settled {
+            break;
+        }
+        printer = Printer::new(options, padding);
+        printer.run(doc);
+    }
+
+    trim_trailing_spaces(&mut printer.out);
+    let len = printer.out.len();
+    let marks = printer.marks.into_iter().map(|mark| mark.min(len)).collect();
+    (printer.out, marks)
+}
+
+/// Where the text of a [`Doc::Align`] was printed.
+struct Aligned {
+    set: usize,
+    start_line: usize,
+    end_line: usize,
+    column: usize,
+}
+
+struct Printer<'a> {
+    options: &'a PrintOptions<'a>,
+    out: String,
+    line: usize,
+    column: usize,
+    pending_newlines: usize,
+    pending_indent: usize,
+    /// Indentation of the line being printed.
+    line_indent: usize,
+    marks: Vec<usize>,
+    /// Marks met while line breaks were pending, which take the offset of the next text.
+    pending_marks: usize,
+    aligned: Vec<Aligned>,
+    /// Spaces to add after every [`Doc::Align`], in document order.
+    padding: Vec<usize>,
+}
+
+impl<'a> Printer<'a> {
+    fn new(options: &'a PrintOptions<'a>, padding: Vec<usize>) -> Self {
+        Self {
+            options,
+            out: String::new(),
+            line: 0,
+            column: 0,
+            pending_newlines: 0,
+            pending_indent: 0,
+            line_indent: 0,
+            marks: Vec::new(),
+            pending_marks: 0,
+            aligned: Vec::new(),
+            padding,
+        }
+    }
+
+    fn run(&mut self, doc: &'a Doc) {
+        let mut commands = vec![Command { indent: 0, mode: Mode::Break, doc }];
+        // Open `Align` docs with the stack depth at which their content is done.
+        let mut open: Vec<(usize, usize)> = Vec::new();
+
+        loop {
+            while let Some(&(depth, index)) = open.last()
+                && commands.len() == depth
+            {
+                open.pop();
+                self.end_align(index);
+            }
+
+            let Some(command) = commands.pop() else {
+                self.flush_marks();
+                break;
+            };
+            match command.doc {
+                Doc::Nil => {}
+                Doc::Mark if self.pending_newlines > 0 => self.pending_marks += 1,
+                Doc::Mark => self.marks.push(self.out.len()),
+                Doc::Align { set, doc } => {
+                    open.push((commands.len(), self.aligned.len()));
+                    self.aligned.push(Aligned {
+                        set: *set,
+                        start_line: self.current_line(),
+                        end_line: 0,
+                        column: 0,
+                    });
+                    commands.push(Command { doc, ..command });
+                }
+                Doc::Text(text) => self.write_text(text),
+                Doc::Line(kind) => match (command.mode, kind) {
+                    (Mode::Flat, LineKind::Soft) => {}
+                    (Mode::Flat, LineKind::Space) => self.write_text(" "),
+                    (_, LineKind::Blank(count)) => self.newline(command.indent, count + 1),
+                    _ => self.newline(command.indent, 1),
+                },
+                Doc::Concat(docs) => {
+                    commands.extend(docs.iter().rev().map(|doc| Command { doc, ..command }));
+                }
+                Doc::Indent(doc) => {
+                    commands.push(Command { indent: command.indent + 1, doc, ..command });
+                }
+                Doc::Anchored(doc) => {
+                    let indent = if self.pending_newlines > 0 {
+                        self.pending_indent
+                    } else {
+                        self.line_indent
+                    };
+                    commands.push(Command { indent, doc, ..command });
+                }
+                Doc::Group { doc, should_break } => {
+                    let flat = Command { mode: Mode::Flat, doc, ..command };
+                    if *should_break {
+                        commands.push(Command { mode: Mode::Break, doc, ..command });
+                    } else if command.mode == Mode::Flat || self.fits(flat, &commands) {
+                        commands.push(flat);
+                    } else {
+                        commands.push(Command { mode: Mode::Break, doc, ..command });
+                    }
+                }
+            }
+        }
+    }
+
+    /// Check whether `next` fits in the remaining width of the current line, taking the
+    /// commands that follow it into account up to the next line break.
+    fn fits(&self, next: Command<'a>, rest: &[Command<'a>]) -> bool {
+        let mut remaining = self.options.line_width as isize - self.current_column() as isize;
+        let mut stack = vec![(next.mode, next.doc)];
+        let mut rest_index = rest.len();
+
+        loop {
+            let (mode, doc) = match stack.pop() {
+                Some(item) => item,
+                None => {
+                    if rest_index == 0 {
+                        return true;
+                    }
+                    rest_index -= 1;
+                    (rest[rest_index].mode, rest[rest_index].doc)
+                }
+            };
+
+            match doc {
+                Doc::Nil | Doc::Mark => {}
+                Doc::Text(text) => {
+                    let (width, multiline) = measure(text);
+                    remaining -= width as isize;
+                    if remaining < 0 {
+                        return false;
+                    }
+                    if multiline {
+                        return true;
+                    }
+                }
+                Doc::Line(kind) => match (mode, kind) {
+                    (Mode::Flat, LineKind::Soft) => {}
+                    (Mode::Flat, LineKind::Space) => {
+                        remaining -= 1;
+                        if remaining < 0 {
+                            return false;
+                        }
+                    }
+                    _ => return true,
+                },
+                Doc::Concat(docs) => stack.extend(docs.iter().rev().map(|doc| (mode, doc))),
+                Doc::Indent(doc) | Doc::Anchored(doc) | Doc::Align { doc, .. } => {
+                    stack.push((mode, doc));
+                }
+                Doc::Group { doc, should_break } => {
+                    stack.push((if *should_break { Mode::Break } else { mode }, doc));
+                }
+            }
+        }
+    }
+
+    /// The width of every line printed so far, in the columns [`Self::current_column`] counts.
+    fn line_widths(&self) -> Vec<usize> {
+        let prefix = self.options.line_prefix.len();
+        self.out
+            .split('\n')
+            .enumerate()
+            .map(|(line, text)| {
+                let text = if line > 0 { text.get(prefix..).unwrap_or(text) } else { text };
+                let indented = text.trim_start_matches('\t');
+                (text.len() - indented.len()) * self.options.indent_width + indented.chars().count()
+            })
+            .collect()
+    }
+
+    fn end_align(&mut self, index: usize) {
+        let end_line = self.current_line();
+        let column = self.current_column();
+        let aligned = &mut self.aligned[index];
+        aligned.end_line = end_line;
+        aligned.column = column;
+
+        let width = self.padding.get(index).copied().unwrap_or(0);
+        self.write_text(&" ".repeat(width));
+    }
+
+    fn current_line(&self) -> usize {
+        self.line + self.pending_newlines
+    }
+
+    fn current_column(&self) -> usize {
+        if self.pending_newlines > 0 {
+            self.pending_indent * self.options.indent_width
+        } else {
+            self.column
+        }
+    }
+
+    fn newline(&mut self, indent: usize, count: usize) {
+        self.pending_newlines = self.pending_newlines.max(count);
+        self.pending_indent = indent;
+    }
+
+    fn write_text(&mut self, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+
+        if self.pending_newlines > 0 {
+            if text.bytes().all(|byte| byte == b' ') {
+                return;
+            }
+            self.flush_newlines();
+            self.flush_marks();
+        }
+
+        self.out.push_str(text);
+        self.line += text.matches('\n').count();
+        match text.rfind('\n') {
+            Some(index) => self.column = text[index + 1..].chars().count(),
+            None => self.column += text.chars().count(),
+        }
+    }
+
+    fn flush_marks(&mut self) {
+        let marks = std::mem::take(&mut self.pending_marks);
+        self.marks.extend(std::iter::repeat_n(self.out.len(), marks));
+    }
+
+    fn flush_newlines(&mut self) {
+        let newlines = std::mem::take(&mut self.pending_newlines);
+        trim_trailing_spaces(&mut self.out);
+        if !self.out.is_empty() {
+            self.out.extend(std::iter::repeat_n('\n', newlines));
+            self.out.push_str(self.options.line_prefix);
+            self.line += newlines;
+        }
+
+        if self.options.use_tabs {
+            self.out.extend(std::iter::repeat_n('\t', self.pending_indent));
+        } else {
+            let width = self.pending_indent * self.options.indent_width;
+            self.out.extend(std::iter::repeat_n(' ', width));
+        }
+        self.column = self.pending_indent * self.options.indent_width;
+        self.line_indent = self.pending_indent;
+    }
+}
+
+/// Choose the padding of every [`Aligned`] text from where `printer` put it.
+///
+/// A set is only lined up when its texts each sit on a line of their own; texts spanning
+/// several lines are left out. Its column is the one that lines up the most texts without adding
+/// more than `max_align_padding` spaces to any of them or pushing their line past the line
+/// width; texts that end past that column or would need more padding or room are left alone.
+fn padding(printer: &Printer, options: &PrintOptions) -> Vec<usize> {
+    let aligned = &printer.aligned;
+    let line_widths = printer.line_widths();
+    // How much of its line follows each text, leaving out the padding it was printed with.
+    let tails: Vec<usize> = aligned
+        .iter()
+        .enumerate()
+        .map(|(index, aligned)| {
+            let printed = aligned.column + printer.padding.get(index).copied().unwrap_or(0);
+            line_widths.get(aligned.end_line).map_or(0, |width| width.saturating_sub(printed))
+        })
+        .collect();
+
+    let mut padding = vec![0; aligned.len()];
+    let mut sets: Vec<usize> = aligned.iter().map(|aligned| aligned.set).collect();
+    sets.sort_unstable();
+    sets.dedup();
+
+    for set in sets {
+        let members: Vec<usize> = (0..aligned.len())
+            .filter(|&index| aligned[index].set == set)
+            .filter(|&index| aligned[index].start_line == aligned[index].end_line)
+            .collect();
+        let mut lines: Vec<usize> = members.iter().map(|&index| aligned[index].end_line).collect();
+        lines.sort_unstable();
+        lines.dedup();
+        if lines.len() != members.len() {
+            continue;
+        }
+
+        let reaches = |column: usize, index: usize| {
+            let end = aligned[index].column;
+            end <= column
+                && column - end <= options.max_align_padding
+                && column + tails[index] <= options.line_width
+        };
+        let best = members
+            .iter()
+            .map(|&index| aligned[index].column)
+            .map(|column| (members.iter().filter(|&&index| reaches(column, index)).count(), column))
+            .max_by(|left, right| left.0.cmp(&right.0).then(right.1.cmp(&left.1)));
+
+        if let Some((count, column)) = best
+            && count > 1
+        {
+            for &index in &members {
+                if reaches(column, index) {
+                    padding[index] = column - aligned[index].column;
+                }
+            }
+        }
+    }
+
+    padding
+}
+
+fn measure(text: &str) -> (usize, bool) {
+    match text.find('\n') {
+        Some(index) => (text[..index].chars().count(), true),
+        None => (text.chars().count(), false),
+    }
+}
+
+fn trim_trailing_spaces(out: &mut String) {
+    let trimmed = out.trim_end_matches([' ', '\t']).len();
+    out.truncate(trimmed);
+}
@@ -0,0 +1,146 @@
+use tidysql_config::Format;
+use tidysql_syntax::{
+    DialectKind, SyntaxElement, SyntaxKind, SyntaxNode, SyntaxTree, TextEdit, TextLen, TextRange,
+    TextSize,
+};
+
+use crate::directives::Verbatim;
+use crate::layout::Formatter;
+use crate::printer::{self, PrintOptions};
+use crate::tokens::{first_code_token, last_code_token};
+use crate::{FormatError, verify};
+
+/// Format the smallest statement or clause that encloses `range`.
+///
+/// Only nodes that start their own line are formatted on their own; they keep the indentation
+/// of that line, and the comments before and after them are left untouched. When no such node
+/// encloses the range, the whole document is formatted. An empty list means the selection is
+/// already formatted.
+///
+/// With `verify` enabled, the edited document is reparsed and checked for dropped or changed
+/// tokens and comments. Idempotency is only checked when the whole document is formatted.
+pub fn format_range(
+    source: &str,
+    range: TextRange,
+    dialect: DialectKind,
+    format: &Format,
+) -> Result<Vec<TextEdit>, FormatError> {
+    let tree = tidysql_syntax::parse(source, dialect).map_err(FormatError::Parse)?;
+    let end = source.text_len();
+    let range =
+        trim_whitespace(source, TextRange::new(range.start().min(end), range.end().min(end)));
+
+    let Some(node) = enclosing_node(&tree, range) else {
+        let formatted = crate::format_verified(&tree, dialect, format)?;
+        if formatted == source {
+            return Ok(Vec::new());
+        }
+        return Ok(vec![TextEdit::replace(TextRange::up_to(end), formatted)]);
+    };
+
+    let Some(code) = code_range(&node) else {
+        return Ok(Vec::new());
+    };
+    let start = usize::from(code.start());
+    let end = usize::from(code.end());
+
+    let line_start = source[..start].rfind('\n').map_or(0, |index| index + 1);
+    let indent = &source[line_start..start];
+
+    let verbatim = Verbatim::build(&tree);
+    if verbatim.regions.iter().any(|region| region.source.intersect(code).is_some()) {
+        return Ok(Vec::new());
+    }
+
+    let doc = Formatter::new(&tree, dialect, format, &verbatim).format_node(&node);
+    let replacement = printer::print(&doc, &PrintOptions::new(format, indent));
+
+    if replacement == source[start..end] {
+        return Ok(Vec::new());
+    }
+
+    if format.verify {
+        let edited = format!("{}{replacement}{}", &source[..start], &source[end..]);
+        let output = tidysql_syntax::parse(&edited, dialect)
+            .map_err(|error| FormatError::Verify(verify::VerifyError::Parse(error)))?;
+        verify::verify_equivalent(&tree, &output, dialect, format).map_err(FormatError::Verify)?;
+    }
+
+    Ok(vec![TextEdit::replace(code, replacement)])
+}
+
+/// Shrink `range` to exclude surrounding whitespace, so that selecting whole lines covers the
+/// code on them rather than the gaps between statements.
+fn trim_whitespace(source: &str, range: TextRange) -> TextRange {
+    let selected = &source[range];
+    let trimmed = selected.trim();
+    if trimmed.is_empty() {
+        return range;
+    }
+
+    let leading = selected.len() - selected.trim_start().len();
+    let start = range.start() + TextSize::of(&selected[..leading]);
+    TextRange::at(start, TextSize::of(trimmed))
+}
+
+/// Find the innermost statement or clause around `range` that starts its own line.
+fn enclosing_node(tree: &SyntaxTree, range: TextRange) -> Option<SyntaxNode> {
+    let mut covering = match tree.root().covering_element(range) {
+        SyntaxElement::Node(node) => node,
+        SyntaxElement::Token(token) => token.parent(),
+    };
+
+    // A selection of whole statements also covers their terminators, which belong to the file.
+    if covering.kind() == SyntaxKind::File {
+        let mut statements = covering.children_with_tokens().filter_map(|child| {
+            let node = child.as_node()?;
+            let code = code_range(node)?;
+            code.intersect(range).is_some_and(|overlap| !overlap.is_empty()).then(|| node.clone())
+        });
+        if let (Some(statement), None) = (statements.next(), statements.next()) {
+            covering = statement;
+        }
+    }
+
+    covering.ancestors().find(|node| {
+        let is_candidate = is_range_root(node.kind())
+            || node.parent().is_some_and(|parent| parent.kind() == SyntaxKind::File);
+        node.kind() != SyntaxKind::File && is_candidate && starts_line(tree.text(), node)
+    })
+}
+
+/// The range from the first to the last code token of `node`, without surrounding trivia.
+fn code_range(node: &SyntaxNode) -> Option<TextRange> {
+    let element = SyntaxElement::Node(node.clone());
+    let first = first_code_token(&element)?;
+    let last = last_code_token(&element)?;
+    Some(TextRange::new(first.text_range().start(), last.text_range().end()))
+}
+
+fn is_range_root(kind: SyntaxKind) -> bool {
+    matches!(
+        kind,
+        SyntaxKind::Statement
+            | SyntaxKind::SelectStatement
+            | SyntaxKind::SetExpression
+            | SyntaxKind::WithCompoundStatement
+            | SyntaxKind::CommonTableExpression
+            | SyntaxKind::SelectClause
+            | SyntaxKind::FromClause
+            | SyntaxKind::WhereClause
+            | SyntaxKind::GroupbyClause
+            | SyntaxKind::HavingClause
+            | SyntaxKind::OrderbyClause
+            | SyntaxKind::LimitClause
+            | SyntaxKind::QualifyClause
+    )
+}
+
+fn starts_line(text: &str, node: &SyntaxNode) -> bool {
+    let Some(code) = code_range(node) else {
+        return false;
+    };
+    let start = usize::from(code.start());
+    let line_start = text[..start].rfind('\n').map_or(0, |index| index + 1);
+    text[line_start..start].trim().is_empty()
+}
@@ -0,0 +1,55 @@
+/// Padding that lines up the cells of rows printed one per line, like a table.
+///
+/// `widths[row]` holds the width of every cell of a row, including the comma after it, or
+/// `None` when the cell does not fit on one line. `offsets[row]` is the width printed before
+/// the row on its line, and cells are separated by a space. Each column is lined up like the
+/// sets of [`crate::doc::Doc::Align`]: at the offset that the most cells reach with at most
+/// `max_padding` spaces, provided there are at least two. Cells that would need more are left
+/// as is, and so are the cells after a cell of unknown width. The last cell of a row is never
+/// padded.
+pub(crate) fn column_padding(
+    widths: &[Vec<Option<usize>>],
+    offsets: &[usize],
+    max_padding: usize,
+) -> Vec<Vec<usize>> {
+    let mut padding: Vec<Vec<usize>> = widths.iter().map(|row| vec![0; row.len()]).collect();
+    let mut positions: Vec<Option<usize>> = offsets.iter().map(|&offset| Some(offset)).collect();
+    let columns = widths.iter().map(Vec::len).max().unwrap_or(0);
+
+    for column in 0..columns.saturating_sub(1) {
+        let ends: Vec<Option<usize>> = widths
+            .iter()
+            .zip(&positions)
+            .map(|(row, position)| {
+                if column + 1 >= row.len() {
+                    return None;
+                }
+                Some(position.as_ref()? + row[column]?)
+            })
+            .collect();
+
+        let reaches = |target: usize, end: usize| end <= target && target - end <= max_padding;
+        let target = ends
+            .iter()
+            .flatten()
+            .map(|&target| {
+                (ends.iter().flatten().filter(|&&end| reaches(target, end)).count(), target)
+            })
+            .max_by(|left, right| left.0.cmp(&right.0).then(right.1.cmp(&left.1)))
+            .filter(|&(count, _)| count >= 2)
+            .map(|(_, target)| target);
+
+        for (row, end) in ends.iter().enumerate() {
+            positions[row] = match (*end, target) {
+                (Some(end), Some(target)) if reaches(target, end) => {
+                    padding[row][column] = target - end;
+                    Some(target + 1)
+                }
+                (Some(end), _) => Some(end + 1),
+                (None, _) => None,
+            };
+        }
+    }
+
+    padding
+}
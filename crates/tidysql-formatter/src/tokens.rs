@@ -0,0 +1,76 @@
+use tidysql_config::{CaseStyle, Format};
+use tidysql_syntax::{
+    DialectKind, SyntaxElement, SyntaxKind, SyntaxNode, SyntaxToken, is_case_sensitive_identifier,
+};
+
+pub(crate) fn is_comment(kind: SyntaxKind) -> bool {
+    matches!(kind, SyntaxKind::Comment | SyntaxKind::InlineComment | SyntaxKind::BlockComment)
+}
+
+pub(crate) fn is_trivia(kind: SyntaxKind) -> bool {
+    matches!(kind, SyntaxKind::Whitespace | SyntaxKind::Newline) || is_comment(kind)
+}
+
+/// A token that carries source text, as opposed to trivia or zero-width meta tokens such as
+/// indents and the end-of-file marker.
+pub(crate) fn is_code(token: &SyntaxToken) -> bool {
+    !token.text().is_empty() && !is_trivia(token.kind())
+}
+
+pub(crate) fn first_code_token(element: &SyntaxElement) -> Option<SyntaxToken> {
+    match element {
+        SyntaxElement::Token(token) => is_code(token).then(|| token.clone()),
+        SyntaxElement::Node(node) => {
+            node.children_with_tokens().find_map(|child| first_code_token(&child))
+        }
+    }
+}
+
+pub(crate) fn last_code_token(element: &SyntaxElement) -> Option<SyntaxToken> {
+    match element {
+        SyntaxElement::Token(token) => is_code(token).then(|| token.clone()),
+        SyntaxElement::Node(node) => {
+            node.children_with_tokens().rev().find_map(|child| last_code_token(&child))
+        }
+    }
+}
+
+pub(crate) fn is_keyword(element: &SyntaxElement, keyword: &str) -> bool {
+    match element {
+        SyntaxElement::Token(token) => {
+            token.kind() == SyntaxKind::Keyword && token.text().eq_ignore_ascii_case(keyword)
+        }
+        SyntaxElement::Node(_) => false,
+    }
+}
+
+/// Whether `element` is a MySQL `DELIMITER` command, which sets the statement terminator for
+/// the rest of the script.
+pub(crate) fn is_delimiter_command(element: &SyntaxElement) -> bool {
+    first_code_token(element).is_some_and(|token| token.text().eq_ignore_ascii_case("delimiter"))
+}
+
+pub(crate) fn token_kind(element: &SyntaxElement) -> Option<SyntaxKind> {
+    element.as_token().map(SyntaxToken::kind)
+}
+
+pub(crate) fn node_kind(element: &SyntaxElement) -> Option<SyntaxKind> {
+    element.as_node().map(SyntaxNode::kind)
+}
+
+/// The case `format` asks for on `token`: keyword case for keywords and word operators such as
+/// `AND`, identifier case for unquoted identifiers whose case is not significant in `dialect`.
+pub(crate) fn case_style(token: &SyntaxToken, dialect: DialectKind, format: &Format) -> CaseStyle {
+    match token.kind() {
+        SyntaxKind::Keyword => format.keyword_case,
+        SyntaxKind::BinaryOperator | SyntaxKind::ComparisonOperator
+            if token.text().bytes().all(|byte| byte.is_ascii_alphabetic()) =>
+        {
+            format.keyword_case
+        }
+        SyntaxKind::NakedIdentifier if !is_case_sensitive_identifier(dialect, token) => {
+            format.identifier_case
+        }
+        _ => CaseStyle::Preserve,
+    }
+}
@@ -0,0 +1,249 @@
+use std::fmt;
+
+use tidysql_config::{CaseStyle, Format, SemicolonStyle};
+use tidysql_syntax::{DialectKind, ParseError, SyntaxElement, SyntaxKind, SyntaxToken, SyntaxTree};
+
+use crate::tokens::{case_style, is_code, is_comment};
+
+/// An invariant the formatted output failed to uphold.
+#[derive(Debug)]
+pub enum VerifyError {
+    /// The formatted output no longer parses.
+    Parse(ParseError),
+    /// A code token was changed, dropped or added. `offset` points into the source.
+    TokenChanged { offset: usize, expected: Option<String>, found: Option<String> },
+    /// A comment was changed, dropped, added or reordered. `offset` points into the source.
+    CommentChanged { offset: usize, expected: Option<String>, found: Option<String> },
+    /// Formatting the output again produced different text.
+    NotIdempotent,
+}
+
+impl fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerifyError::Parse(error) => write!(f, "formatted output does not parse: {error}"),
+            VerifyError::TokenChanged { offset, expected, found } => {
+                write!(f, "formatting changed the token at offset {offset}: ")?;
+                describe_change(f, expected.as_deref(), found.as_deref())
+            }
+            VerifyError::CommentChanged { offset, expected, found } => {
+                write!(f, "formatting changed the comment at offset {offset}: ")?;
+                describe_change(f, expected.as_deref(), found.as_deref())
+            }
+            VerifyError::NotIdempotent => {
+                write!(f, "formatting the output again changes it")
+            }
+        }
+    }
+}
+
+fn describe_change(
+    f: &mut fmt::Formatter<'_>,
+    expected: Option<&str>,
+    found: Option<&str>,
+) -> fmt::Result {
+    match (expected, found) {
+        (Some(expected), Some(found)) => write!(f, "expected `{expected}`, found `{found}`"),
+        (Some(expected), None) => write!(f, "`{expected}` was dropped"),
+        (None, Some(found)) => write!(f, "`{found}` was added"),
+        (None, None) => Ok(()),
+    }
+}
+
+impl std::error::Error for VerifyError {}
+
+/// Check that `formatted`, the output of formatting `tree`, is safe to emit: it parses to the
+/// same code tokens, keeps every comment in order, and is a fixed point of the formatter.
+pub(crate) fn verify(
+    tree: &SyntaxTree,
+    formatted: &str,
+    dialect: DialectKind,
+    format: &Format,
+) -> Result<(), VerifyError> {
+    let output = tidysql_syntax::parse(formatted, dialect).map_err(VerifyError::Parse)?;
+    verify_equivalent(tree, &output, dialect, format)?;
+
+    if crate::format_tree(&output, dialect, format) != formatted {
+        return Err(VerifyError::NotIdempotent);
+    }
+
+    Ok(())
+}
+
+/// Check that `output` has the same code tokens and comments as `tree`.
+pub(crate) fn verify_equivalent(
+    tree: &SyntaxTree,
+    output: &SyntaxTree,
+    dialect: DialectKind,
+    format: &Format,
+) -> Result<(), VerifyError> {
+    compare(code_tokens(tree, format), code_tokens(output, format), |expected, found| {
+        expected.kind() == found.kind() && same_text(expected, found, dialect, format)
+    })
+    .map_err(|(offset, expected, found)| VerifyError::TokenChanged {
+        offset,
+        expected,
+        found,
+    })?;
+
+    compare(comments(tree), comments(output), |expected, found| expected.text() == found.text())
+        .map_err(|(offset, expected, found)| VerifyError::CommentChanged {
+            offset,
+            expected,
+            found,
+        })
+}
+
+type Mismatch = (usize, Option<String>, Option<String>);
+
+fn compare(
+    expected: Vec<SyntaxToken>,
+    found: Vec<SyntaxToken>,
+    same: impl Fn(&SyntaxToken, &SyntaxToken) -> bool,
+) -> Result<(), Mismatch> {
+    let mut expected = expected.into_iter();
+    let mut found = found.into_iter();
+    let mut end = 0;
+
+    loop {
+        match (expected.next(), found.next()) {
+            (None, None) => return Ok(()),
+            (Some(expected), Some(found)) if same(&expected, &found) => {
+                end = usize::from(expected.text_range().end());
+            }
+            (expected, found) => {
+                let offset =
+                    expected.as_ref().map_or(end, |token| usize::from(token.text_range().start()));
+                let text = |token: Option<SyntaxToken>| token.map(|token| token.text().to_string());
+                return Err((offset, text(expected), text(found)));
+            }
+        }
+    }
+}
+
+/// Case changes requested by the configuration are the only edits allowed inside a token.
+fn same_text(
+    expected: &SyntaxToken,
+    found: &SyntaxToken,
+    dialect: DialectKind,
+    format: &Format,
+) -> bool {
+    if case_style(expected, dialect, format) == CaseStyle::Preserve {
+        expected.text() == found.text()
+    } else {
+        expected.text().to_lowercase() == found.text().to_lowercase()
+    }
+}
+
+/// The code tokens of `tree`, leaving out statement terminators when `format` adds or removes
+/// them.
+fn code_tokens(tree: &SyntaxTree, format: &Format) -> Vec<SyntaxToken> {
+    let terminators = format.semicolons == SemicolonStyle::Preserve;
+    tree.root()
+        .descendants_with_tokens()
+        .filter_map(|element| match element {
+            SyntaxElement::Token(token)
+                if is_code(&token)
+                    && (terminators || token.kind() != SyntaxKind::StatementTerminator) =>
+            {
+                Some(token)
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+fn comments(tree: &SyntaxTree) -> Vec<SyntaxToken> {
+    let mut comments = Vec::new();
+    for element in tree.root().descendants_with_tokens() {
+        let SyntaxElement::Token(token) = element else { continue };
+        comments.extend(token.leading_trivia().filter(|trivia| is_comment(trivia.kind())));
+        comments.extend(token.trailing_trivia().filter(|trivia| is_comment(trivia.kind())));
+    }
+    comments
+}
+
+#[cfg(test)]
+mod tests {
+    use tidysql_config::{CaseStyle, Format};
+    use tidysql_syntax::DialectKind;
+
+    use super::{VerifyError, verify, verify_equivalent};
+
+    fn check(source: &str, formatted: &str, format: &Format) -> Result<(), VerifyError> {
+        let tree = tidysql_syntax::parse(source, DialectKind::Ansi).unwrap();
+        verify(&tree, formatted, DialectKind::Ansi, format)
+    }
+
+    fn check_equivalent(source: &str, output: &str, format: &Format) -> Result<(), VerifyError> {
+        let tree = tidysql_syntax::parse(source, DialectKind::Ansi).unwrap();
+        let output = tidysql_syntax::parse(output, DialectKind::Ansi).unwrap();
+        verify_equivalent(&tree, &output, DialectKind::Ansi, format)
+    }
+
+    #[test]
+    fn accepts_formatted_output() {
+        let format = Format { keyword_case: CaseStyle::Upper, ..Format::default() };
+        check("select a, b from t", "SELECT a, b FROM t\n", &format).unwrap();
+    }
+
+    #[test]
+    fn reports_output_that_does_not_parse() {
+        let error = check("SELECT a FROM t", "SELECT a FROM WHERE (\n", &Format::default());
+        assert!(matches!(error, Err(VerifyError::Parse(_))), "{error:?}");
+    }
+
+    #[test]
+    fn reports_changed_tokens() {
+        let error = check_equivalent("SELECT a FROM t", "SELECT b FROM t", &Format::default());
+        assert!(
+            matches!(
+                &error,
+                Err(VerifyError::TokenChanged { offset: 7, expected: Some(expected), found: Some(found) })
+                    if expected == "a" && found == "b"
+            ),
+            "{error:?}"
+        );
+    }
+
+    #[test]
+    fn reports_dropped_tokens() {
+        let error = check_equivalent("SELECT a, b FROM t", "SELECT a FROM t", &Format::default());
+        assert!(matches!(error, Err(VerifyError::TokenChanged { offset: 8, .. })), "{error:?}");
+    }
+
+    #[test]
+    fn reports_case_changes_the_format_does_not_ask_for() {
+        let error = check_equivalent("select a from t", "SELECT a FROM t", &Format::default());
+        assert!(matches!(error, Err(VerifyError::TokenChanged { offset: 0, .. })), "{error:?}");
+    }
+
+    #[test]
+    fn reports_changed_comments() {
+        let error = check_equivalent(
+            "SELECT a -- first\nFROM t",
+            "SELECT a -- second\nFROM t",
+            &Format::default(),
+        );
+        assert!(matches!(error, Err(VerifyError::CommentChanged { offset: 9, .. })), "{error:?}");
+    }
+
+    #[test]
+    fn reports_dropped_comments() {
+        let error =
+            check_equivalent("SELECT a /* kept */ FROM t", "SELECT a FROM t", &Format::default());
+        assert!(
+            matches!(
+                &error,
+                Err(VerifyError::CommentChanged { expected: Some(_), found: None, .. })
+            ),
+            "{error:?}"
+        );
+    }
+
+    #[test]
+    fn reports_output_that_formats_differently() {
+        let error = check("SELECT a FROM t", "SELECT   a FROM t\n", &Format::default());
+        assert!(matches!(error, Err(VerifyError::NotIdempotent)), "{error:?}");
+    }
+}
@@ -0,0 +1,193 @@
+use std::path::Path;
+
+use serde::Deserialize;
+use tidysql_config::{Config, Dialect, Format};
+use tidysql_syntax::{DialectKind, SyntaxElement, SyntaxKind, SyntaxTree, TextRange, TextSize};
+
+#[derive(Deserialize)]
+struct FormatSuite {
+    #[serde(default, rename = "case")]
+    cases: Vec<FormatCase>,
+}
+
+#[derive(Deserialize)]
+struct FormatCase {
+    #[serde(default)]
+    name: Option<String>,
+    sql: String,
+    #[serde(default)]
+    config: Config,
+    /// Lines to format with `format_range`, as `START:END` (1-based, inclusive). The whole
+    /// input is formatted when unset.
+    #[serde(default)]
+    range: Option<String>,
+    formatted_sql: String,
+}
+
+fn run_case(path: &Path, input: String) -> datatest_stable::Result<()> {
+    let suite: FormatSuite = toml::from_str(&input)?;
+
+    if suite.cases.is_empty() {
+        return Err(format!("no cases found in {}", path.display()).into());
+    }
+
+    for (case_index, case) in suite.cases.iter().enumerate() {
+        run_single_case(path, case_index, case)?;
+    }
+
+    Ok(())
+}
+
+fn run_single_case(
+    path: &Path,
+    case_index: usize,
+    case: &FormatCase,
+) -> datatest_stable::Result<()> {
+    let label = case_label(case, case_index);
+    let dialect = config_dialect(&case.config);
+    let format = &case.config.format;
+
+    if let Some(lines) = &case.range {
+        return run_range_case(path, &label, case, lines, dialect);
+    }
+
+    // Format without the built-in verification so that the checks below report what went wrong.
+    let tree =
+        tidysql_syntax::parse(&case.sql, dialect).map_err(|error| format!("{label}: {error}"))?;
+    let formatted = tidysql_formatter::format_tree(&tree, dialect, format);
+    assert_eq!(
+        formatted,
+        case.formatted_sql,
+        "formatted sql mismatch ({label}) in {}",
+        path.display()
+    );
+
+    let reparsed = tidysql_syntax::parse(&formatted, dialect)
+        .map_err(|error| format!("{label}: formatted sql does not parse: {error}"))?;
+    assert_eq!(
+        comments(&reparsed),
+        comments(&tree),
+        "comments changed ({label}) in {}",
+        path.display(),
+    );
+
+    let again = tidysql_formatter::format_tree(&reparsed, dialect, format);
+    assert_eq!(again, formatted, "formatting is not idempotent ({label}) in {}", path.display());
+
+    // The built-in verification has to accept every output the checks above accept.
+    let verified = Format { verify: true, ..format.clone() };
+    let verified = tidysql_formatter::format_with_dialect(&case.sql, dialect, &verified)
+        .map_err(|error| format!("{label}: {error}"))?;
+    assert_eq!(verified, formatted, "verified sql mismatch ({label}) in {}", path.display());
+
+    Ok(())
+}
+
+fn run_range_case(
+    path: &Path,
+    label: &str,
+    case: &FormatCase,
+    lines: &str,
+    dialect: DialectKind,
+) -> datatest_stable::Result<()> {
+    let format = &case.config.format;
+    let range = line_range(&case.sql, lines).map_err(|error| format!("{label}: {error}"))?;
+
+    let edits = tidysql_formatter::format_range(&case.sql, range, dialect, format)
+        .map_err(|error| format!("{label}: {error}"))?;
+    let formatted = tidysql_syntax::apply_edits(&case.sql, edits)
+        .map_err(|error| format!("{label}: failed to apply edits: {error:?}"))?;
+    assert_eq!(
+        formatted,
+        case.formatted_sql,
+        "formatted sql mismatch ({label}) in {}",
+        path.display()
+    );
+
+    let tree =
+        tidysql_syntax::parse(&case.sql, dialect).map_err(|error| format!("{label}: {error}"))?;
+    let reparsed = tidysql_syntax::parse(&formatted, dialect)
+        .map_err(|error| format!("{label}: formatted sql does not parse: {error}"))?;
+    assert_eq!(
+        comments(&reparsed),
+        comments(&tree),
+        "comments changed ({label}) in {}",
+        path.display(),
+    );
+
+    let range = line_range(&formatted, lines).map_err(|error| format!("{label}: {error}"))?;
+    let again = tidysql_formatter::format_range(&formatted, range, dialect, format)
+        .map_err(|error| format!("{label}: {error}"))?;
+    assert!(
+        again.is_empty(),
+        "formatting the range is not idempotent ({label}) in {}",
+        path.display()
+    );
+
+    Ok(())
+}
+
+/// The text range covering lines `START:END` of `text`.
+fn line_range(text: &str, lines: &str) -> Result<TextRange, String> {
+    let (start, end) = lines.split_once(':').ok_or_else(|| format!("invalid range `{lines}`"))?;
+    let parse = |line: &str| line.parse::<usize>().map_err(|_| format!("invalid range `{lines}`"));
+    let (start, end) = (parse(start)?, parse(end)?);
+
+    let line_starts: Vec<usize> =
+        std::iter::once(0).chain(text.match_indices('\n').map(|(index, _)| index + 1)).collect();
+    let start = *line_starts.get(start - 1).ok_or_else(|| format!("no line {start}"))?;
+    let end = line_starts.get(end).map_or(text.len(), |&next_line| next_line - 1).max(start);
+
+    let size = |offset: usize| TextSize::new(offset as u32);
+    Ok(TextRange::new(size(start), size(end)))
+}
+
+/// The text of every comment in `tree`, in order.
+fn comments(tree: &SyntaxTree) -> Vec<String> {
+    tree.root()
+        .descendants_with_tokens()
+        .filter_map(SyntaxElement::into_token)
+        .flat_map(|token| token.leading_trivia().chain(token.trailing_trivia()))
+        .filter(|trivia| {
+            matches!(
+                trivia.kind(),
+                SyntaxKind::Comment | SyntaxKind::InlineComment | SyntaxKind::BlockComment
+            )
+        })
+        .map(|comment| comment.text().to_string())
+        .collect()
+}
+
+fn config_dialect(config: &Config) -> DialectKind {
+    match config.core.dialect {
+        Dialect::Ansi => DialectKind::Ansi,
+        Dialect::Athena => DialectKind::Athena,
+        Dialect::Bigquery => DialectKind::Bigquery,
+        Dialect::Clickhouse => DialectKind::Clickhouse,
+        Dialect::Databricks => DialectKind::Databricks,
+        Dialect::Duckdb => DialectKind::Duckdb,
+        Dialect::Mysql => DialectKind::Mysql,
+        Dialect::Postgres => DialectKind::Postgres,
+        Dialect::Redshift => DialectKind::Redshift,
+        Dialect::Snowflake => DialectKind::Snowflake,
+        Dialect::Sparksql => DialectKind::Sparksql,
+        Dialect::Sqlite => DialectKind::Sqlite,
+        Dialect::Trino => DialectKind::Trino,
+        Dialect::Tsql => DialectKind::Tsql,
+    }
+}
+
+fn case_label(case: &FormatCase, case_index: usize) -> String {
+    match &case.name {
+        Some(name) => format!("{name} (#{case_index})"),
+        None => format!("case #{case_index}"),
+    }
+}
+
+datatest_stable::harness! {
+    {
+        test = run_case,
+        root = concat!(env!("CARGO_MANIFEST_DIR"), "/tests"),
+        pattern = r"^.*\.toml$",
+    },
+}
@@ -0,0 +1,124 @@
+use tidysql_syntax::{DialectKind, SyntaxKind, SyntaxNode};
+
+use crate::sources::{is_in_scope, name_parts, same_name, sources};
+use crate::{Diagnostic, LintContext, NodeLint, Severity};
+
+pub(crate) struct AmbiguousColumnReference;
+
+impl NodeLint for AmbiguousColumnReference {
+    const CODE: &'static str = "ambiguous_column_reference";
+    const MESSAGE: &'static str =
+        "Qualify column references when a query reads from several tables.";
+    const SEVERITY: Severity = Severity::Warn;
+    const TARGET: SyntaxKind = SyntaxKind::SelectStatement;
+
+    fn level(config: &tidysql_config::Config) -> Severity {
+        config.lints.ambiguous_column_reference.level
+    }
+
+    fn check(ctx: &LintContext<'_>, node: &SyntaxNode, diagnostics: &mut Vec<Diagnostic>) {
+        let lint = &ctx.config.lints.ambiguous_column_reference;
+        let options = &lint.options;
+
+        let sources = sources(node);
+        if sources.is_empty() {
+            return;
+        }
+        let check_unqualified = sources.len() > 1 || !options.allow_single_source;
+        let aliases = select_aliases(node);
+
+        for reference in node.descendants() {
+            if reference.kind() != SyntaxKind::ColumnReference || !is_in_scope(node, &reference) {
+                continue;
+            }
+
+            let parts = name_parts(&reference);
+            match parts.as_slice() {
+                // `ORDER BY total` may name an alias of the select list rather than a column.
+                [column]
+                    if check_unqualified
+                        && !is_join_using(&reference)
+                        && !(may_name_alias(ctx.dialect, &reference)
+                            && aliases.iter().any(|alias| same_name(alias, column.text()))) =>
+                {
+                    diagnostics.push(Diagnostic::from_text_range(
+                        Self::CODE,
+                        format!("Qualify column `{}` with the table it comes from.", column.text()),
+                        lint.level,
+                        reference.text_range(),
+                    ));
+                }
+                [.., qualifier, column] if options.require_alias => {
+                    if sources.iter().any(|source| source.is_named(qualifier.text())) {
+                        continue;
+                    }
+                    let alias = sources
+                        .iter()
+                        .find(|source| {
+                            source
+                                .table
+                                .as_ref()
+                                .is_some_and(|table| same_name(table.text(), qualifier.text()))
+                        })
+                        .and_then(|source| source.alias.as_ref());
+                    if let Some(alias) = alias {
+                        diagnostics.push(Diagnostic::from_text_range(
+                            Self::CODE,
+                            format!(
+                                "Qualify column `{}` with the alias `{}` rather than the table \
+                                 name.",
+                                column.text(),
+                                alias.text()
+                            ),
+                            lint.level,
+                            qualifier.text_range(),
+                        ));
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// The aliases given to the items of the select list of `select`.
+fn select_aliases(select: &SyntaxNode) -> Vec<String> {
+    select
+        .children()
+        .filter(|child| child.kind() == SyntaxKind::SelectClause)
+        .flat_map(|clause| clause.children())
+        .filter(|element| element.kind() == SyntaxKind::SelectClauseElement)
+        .flat_map(|element| element.children())
+        .filter(|child| child.kind() == SyntaxKind::AliasExpression)
+        .filter_map(|alias| name_parts(&alias).pop())
+        .map(|alias| alias.text().to_string())
+        .collect()
+}
+
+/// Whether `reference` sits in a clause where `dialect` resolves the aliases of the select list:
+/// `ORDER BY` everywhere, `GROUP BY` and `HAVING` in the dialects that allow it.
+fn may_name_alias(dialect: DialectKind, reference: &SyntaxNode) -> bool {
+    let group_by = !matches!(
+        dialect,
+        DialectKind::Ansi | DialectKind::Athena | DialectKind::Trino | DialectKind::Tsql
+    );
+    let having = group_by && !matches!(dialect, DialectKind::Postgres | DialectKind::Redshift);
+
+    reference.ancestors().take_while(|node| node.kind() != SyntaxKind::SelectStatement).any(
+        |node| match node.kind() {
+            SyntaxKind::OrderbyClause => true,
+            SyntaxKind::GroupbyClause => group_by,
+            SyntaxKind::HavingClause => having,
+            _ => false,
+        },
+    )
+}
+
+/// Columns in `JOIN ... USING (...)` cannot be qualified.
+fn is_join_using(reference: &SyntaxNode) -> bool {
+    reference
+        .parent()
+        .filter(|parent| parent.kind() == SyntaxKind::Bracketed)
+        .and_then(|bracketed| bracketed.parent())
+        .is_some_and(|parent| parent.kind() == SyntaxKind::JoinClause)
+}
@@ -0,0 +1,184 @@
+use tidysql_config::{CapitalisationConfig, CapitalisationPolicy, Config};
+use tidysql_syntax::{DialectKind, Fix, SyntaxElement, SyntaxToken, TextEdit};
+
+use crate::keyword_case::KeywordCase;
+use crate::{Diagnostic, LintContext, TokenLint};
+
+/// A lint that enforces the case of one kind of word: keywords, identifiers, function names,
+/// data types or literals.
+pub(crate) trait CaseLint: TokenLint {
+    /// Plural name of the words the lint checks, as used in messages.
+    const SUBJECT: &'static str;
+    const FIX_TITLE: &'static str;
+
+    fn options(config: &Config) -> &CapitalisationConfig;
+
+    /// Whether `token` is one of the words the lint checks.
+    fn owns(ctx: &LintContext<'_>, token: &SyntaxToken) -> bool;
+
+    /// Whether changing the case of `token` can change what it refers to in `dialect`, in which
+    /// case it is reported without a fix.
+    fn is_case_sensitive(_dialect: DialectKind, _token: &SyntaxToken) -> bool {
+        false
+    }
+}
+
+pub(crate) fn check<L: CaseLint>(
+    ctx: &LintContext<'_>,
+    token: &SyntaxToken,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let options = L::options(ctx.config);
+    let text = token.text();
+
+    if !L::owns(ctx, token) || is_ignored(text, options) {
+        return;
+    }
+
+    let policy = resolve_policy::<L>(options.policy, ctx);
+    if is_correct_case(text, policy) {
+        return;
+    }
+
+    let description = policy_description(policy);
+    let level = L::level(ctx.config);
+    if L::is_case_sensitive(ctx.dialect, token) {
+        diagnostics.push(Diagnostic::from_text_range(
+            L::CODE,
+            format!(
+                "{} must be {description}; `{text}` is not fixed because its case is significant \
+                 in this dialect.",
+                L::SUBJECT
+            ),
+            level,
+            token.text_range(),
+        ));
+        return;
+    }
+
+    let fixed = apply_case(text, policy);
+    let edit = TextEdit::replace(token.text_range(), fixed);
+    let fix = Fix::single(L::FIX_TITLE, edit);
+
+    diagnostics.push(
+        Diagnostic::from_text_range(
+            L::CODE,
+            format!("{} must be {description}.", L::SUBJECT),
+            level,
+            token.text_range(),
+        )
+        .with_fix(fix),
+    );
+}
+
+/// `keyword` in the case `keyword_case` asks for, which is the case most keywords of the file are
+/// in unless it sets a policy, so that keywords added by fixes blend in.
+pub(crate) fn keyword_in_case(ctx: &LintContext<'_>, keyword: &str) -> String {
+    let policy = resolve_policy::<KeywordCase>(KeywordCase::options(ctx.config).policy, ctx);
+    apply_case(keyword, policy)
+}
+
+/// Whether `text` is a plain word, as opposed to a quoted name, a number or punctuation.
+pub(crate) fn is_word(text: &str) -> bool {
+    let mut bytes = text.bytes();
+    bytes.next().is_some_and(|b| b.is_ascii_alphabetic() || b == b'_')
+        && bytes.all(|b| b.is_ascii_alphanumeric() || b == b'_' || b == b'$')
+}
+
+fn policy_description(policy: CapitalisationPolicy) -> &'static str {
+    match policy {
+        CapitalisationPolicy::Consistent => "consistent",
+        CapitalisationPolicy::Upper => "uppercase",
+        CapitalisationPolicy::Lower | CapitalisationPolicy::Snake => "lowercase",
+        CapitalisationPolicy::Pascal | CapitalisationPolicy::Capitalise => "capitalised",
+        CapitalisationPolicy::Camel => "camelCase",
+    }
+}
+
+fn is_ignored(text: &str, options: &CapitalisationConfig) -> bool {
+    options.ignore_words.iter().any(|w| w.eq_ignore_ascii_case(text))
+        || options.ignore_words_regex.iter().any(|r| r.is_match(text))
+}
+
+fn resolve_policy<L: CaseLint>(
+    policy: CapitalisationPolicy,
+    ctx: &LintContext<'_>,
+) -> CapitalisationPolicy {
+    match policy {
+        CapitalisationPolicy::Consistent => infer_policy::<L>(ctx),
+        other => other,
+    }
+}
+
+/// The case most of the words checked by `L` are already in.
+fn infer_policy<L: CaseLint>(ctx: &LintContext<'_>) -> CapitalisationPolicy {
+    let (upper, lower) = ctx
+        .tree
+        .root()
+        .descendants_with_tokens()
+        .filter_map(|el| match el {
+            SyntaxElement::Token(t) if L::matches(t.kind()) && L::owns(ctx, &t) => Some(t),
+            _ => None,
+        })
+        .fold((0usize, 0usize), |(upper, lower), token| {
+            let text = token.text();
+            if is_all_upper(text) {
+                (upper + 1, lower)
+            } else if is_all_lower(text) {
+                (upper, lower + 1)
+            } else {
+                (upper, lower)
+            }
+        });
+
+    if upper >= lower { CapitalisationPolicy::Upper } else { CapitalisationPolicy::Lower }
+}
+
+fn is_correct_case(text: &str, policy: CapitalisationPolicy) -> bool {
+    match policy {
+        CapitalisationPolicy::Consistent => true,
+        CapitalisationPolicy::Upper => is_all_upper(text),
+        CapitalisationPolicy::Lower | CapitalisationPolicy::Snake | CapitalisationPolicy::Camel => {
+            is_all_lower(text)
+        }
+        CapitalisationPolicy::Pascal | CapitalisationPolicy::Capitalise => is_capitalised(text),
+    }
+}
+
+fn apply_case(text: &str, policy: CapitalisationPolicy) -> String {
+    match policy {
+        CapitalisationPolicy::Consistent => text.to_string(),
+        CapitalisationPolicy::Upper => text.to_ascii_uppercase(),
+        CapitalisationPolicy::Lower | CapitalisationPolicy::Snake | CapitalisationPolicy::Camel => {
+            text.to_ascii_lowercase()
+        }
+        CapitalisationPolicy::Pascal | CapitalisationPolicy::Capitalise => capitalise(text),
+    }
+}
+
+fn is_all_upper(text: &str) -> bool {
+    !text.bytes().any(|b| b.is_ascii_lowercase())
+}
+
+fn is_all_lower(text: &str) -> bool {
+    !text.bytes().any(|b| b.is_ascii_uppercase())
+}
+
+fn is_capitalised(text: &str) -> bool {
+    let mut bytes = text.bytes();
+    let first_ok = bytes.next().is_none_or(|b| b.is_ascii_uppercase());
+    let rest_ok = !bytes.any(|b| b.is_ascii_uppercase());
+    first_ok && rest_ok
+}
+
+fn capitalise(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut bytes = text.bytes();
+    if let Some(first) = bytes.next() {
+        result.push(first.to_ascii_uppercase() as char);
+    }
+    for b in bytes {
+        result.push(b.to_ascii_lowercase() as char);
+    }
+    result
+}
@@ -0,0 +1,64 @@
+use tidysql_config::AliasStyle;
+use tidysql_syntax::{Fix, SyntaxElement, SyntaxKind, SyntaxNode, TextEdit, TextRange};
+
+use crate::capitalisation::keyword_in_case;
+use crate::sources::name_parts;
+use crate::{Diagnostic, LintContext, NodeLint, Severity};
+
+pub(crate) struct ConsistentAliasing;
+
+impl NodeLint for ConsistentAliasing {
+    const CODE: &'static str = "consistent_aliasing";
+    const MESSAGE: &'static str = "Write aliases consistently with or without AS.";
+    const SEVERITY: Severity = Severity::Warn;
+    const TARGET: SyntaxKind = SyntaxKind::AliasExpression;
+
+    fn level(config: &tidysql_config::Config) -> Severity {
+        config.lints.consistent_aliasing.level
+    }
+
+    fn check(ctx: &LintContext<'_>, node: &SyntaxNode, diagnostics: &mut Vec<Diagnostic>) {
+        let lint = &ctx.config.lints.consistent_aliasing;
+
+        let (kind, style) = match node.parent().map(|parent| parent.kind()) {
+            Some(SyntaxKind::SelectClauseElement) => ("column", lint.options.column_aliases),
+            Some(SyntaxKind::FromExpressionElement) => ("table", lint.options.table_aliases),
+            _ => return,
+        };
+
+        let Some(name) = name_parts(node).into_iter().next() else { return };
+        let as_keyword =
+            node.children_with_tokens().filter_map(SyntaxElement::into_token).find(|token| {
+                token.kind() == SyntaxKind::Keyword && token.text().eq_ignore_ascii_case("as")
+            });
+
+        let (message, fix) = match (style, as_keyword) {
+            (AliasStyle::Explicit, None) => (
+                format!("Use AS before the {kind} alias `{}`.", name.text()),
+                Fix::single(
+                    "Add AS before the alias",
+                    TextEdit::insert(
+                        name.text_range().start(),
+                        format!("{} ", keyword_in_case(ctx, "AS")),
+                    ),
+                ),
+            ),
+            (AliasStyle::Implicit, Some(as_keyword)) => (
+                format!("Remove AS before the {kind} alias `{}`.", name.text()),
+                Fix::single(
+                    "Remove AS before the alias",
+                    TextEdit::delete(TextRange::new(
+                        as_keyword.text_range().start(),
+                        name.text_range().start(),
+                    )),
+                ),
+            ),
+            _ => return,
+        };
+
+        diagnostics.push(
+            Diagnostic::from_text_range(Self::CODE, message, lint.level, name.text_range())
+                .with_fix(fix),
+        );
+    }
+}
@@ -1,6 +1,6 @@
 use tidysql_syntax::{SyntaxKind, SyntaxToken};
 
-use crate::{Diagnostic, LintContext, Severity, TokenLint};
+use crate::{Diagnostic, LintContext, Severity, TokenLint, strip_identifier_quotes};
 
 pub(crate) struct DisallowNames;
 
@@ -43,16 +43,3 @@ impl TokenLint for DisallowNames {
         ));
     }
 }
-
-fn strip_identifier_quotes(text: &str) -> &str {
-    if text.len() < 2 {
-        return text;
-    }
-
-    let bytes = text.as_bytes();
-    let last = bytes.len() - 1;
-
-    let strip = matches!((bytes[0], bytes[last]), (b'"', b'"') | (b'`', b'`') | (b'[', b']'));
-
-    if strip { &text[1..last] } else { text }
-}
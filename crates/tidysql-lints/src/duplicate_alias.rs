@@ -0,0 +1,96 @@
+use tidysql_syntax::{SyntaxElement, SyntaxKind, SyntaxNode, SyntaxToken};
+
+use crate::sources::{name_parts, same_name, sources};
+use crate::{Diagnostic, LintContext, NodeLint, Severity};
+
+/// Reports two sources of a `SELECT` that go by the same name.
+pub(crate) struct DuplicateAlias;
+
+impl NodeLint for DuplicateAlias {
+    const CODE: &'static str = "duplicate_alias";
+    const MESSAGE: &'static str = "Give every table of a query its own alias.";
+    const SEVERITY: Severity = Severity::Warn;
+    const TARGET: SyntaxKind = SyntaxKind::SelectStatement;
+
+    fn level(config: &tidysql_config::Config) -> Severity {
+        config.lints.duplicate_alias.level
+    }
+
+    fn check(ctx: &LintContext<'_>, node: &SyntaxNode, diagnostics: &mut Vec<Diagnostic>) {
+        let sources = sources(node);
+        for (index, source) in sources.iter().enumerate() {
+            let Some(name) = source.name() else { continue };
+            if !sources[..index].iter().any(|earlier| earlier.is_named(name.text())) {
+                continue;
+            }
+
+            diagnostics.push(Diagnostic::from_text_range(
+                Self::CODE,
+                format!("`{}` already names another table of this query.", name.text()),
+                ctx.config.lints.duplicate_alias.level,
+                name.text_range(),
+            ));
+        }
+    }
+}
+
+/// Reports CTEs of a `WITH` clause that share a name, or that take the name of a table the
+/// clause reads before the CTE is defined.
+pub(crate) struct DuplicateCteName;
+
+impl NodeLint for DuplicateCteName {
+    const CODE: &'static str = "duplicate_alias";
+    const MESSAGE: &'static str = "Give every CTE a name of its own.";
+    const SEVERITY: Severity = Severity::Warn;
+    const TARGET: SyntaxKind = SyntaxKind::WithCompoundStatement;
+
+    fn level(config: &tidysql_config::Config) -> Severity {
+        config.lints.duplicate_alias.level
+    }
+
+    fn check(ctx: &LintContext<'_>, node: &SyntaxNode, diagnostics: &mut Vec<Diagnostic>) {
+        let ctes: Vec<SyntaxNode> = node
+            .children()
+            .filter(|child| child.kind() == SyntaxKind::CommonTableExpression)
+            .collect();
+        let names: Vec<Option<SyntaxToken>> =
+            ctes.iter().map(|cte| name_parts(cte).into_iter().next()).collect();
+        let recursive =
+            node.children_with_tokens().filter_map(SyntaxElement::into_token).any(|token| {
+                token.kind() == SyntaxKind::Keyword
+                    && token.text().eq_ignore_ascii_case("recursive")
+            });
+
+        for (index, name) in names.iter().enumerate() {
+            let Some(name) = name else { continue };
+
+            let message = if names[..index]
+                .iter()
+                .flatten()
+                .any(|earlier| same_name(earlier.text(), name.text()))
+            {
+                format!("CTE `{}` is already defined in this WITH clause.", name.text())
+            } else if !recursive && ctes[..=index].iter().any(|cte| reads_table(cte, name.text())) {
+                // Without `RECURSIVE` a CTE can only read the ones defined before it, so the name
+                // refers to a table up to its own definition and to the CTE after it.
+                format!("CTE `{}` shadows the table of the same name read before it.", name.text())
+            } else {
+                continue;
+            };
+
+            diagnostics.push(Diagnostic::from_text_range(
+                Self::CODE,
+                message,
+                ctx.config.lints.duplicate_alias.level,
+                name.text_range(),
+            ));
+        }
+    }
+}
+
+/// Whether `node` reads from a table called `name`, written without a schema.
+fn reads_table(node: &SyntaxNode, name: &str) -> bool {
+    node.descendants().filter(|node| node.kind() == SyntaxKind::TableReference).any(|reference| {
+        matches!(name_parts(&reference).as_slice(), [table] if same_name(table.text(), name))
+    })
+}
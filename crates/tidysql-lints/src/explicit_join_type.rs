@@ -0,0 +1,81 @@
+use tidysql_config::OuterKeyword;
+use tidysql_syntax::{Fix, SyntaxKind, SyntaxNode, SyntaxToken, TextEdit, TextRange};
+
+use crate::capitalisation::keyword_in_case;
+use crate::join_condition_required::join_keywords;
+use crate::{Diagnostic, LintContext, NodeLint, Severity};
+
+pub(crate) struct ExplicitJoinType;
+
+impl NodeLint for ExplicitJoinType {
+    const CODE: &'static str = "explicit_join_type";
+    const MESSAGE: &'static str = "Use INNER JOIN instead of bare JOIN.";
+    const SEVERITY: Severity = Severity::Warn;
+    const TARGET: SyntaxKind = SyntaxKind::JoinClause;
+
+    fn level(config: &tidysql_config::Config) -> Severity {
+        config.lints.explicit_join_type.level
+    }
+
+    fn check(ctx: &LintContext<'_>, node: &SyntaxNode, diagnostics: &mut Vec<Diagnostic>) {
+        let lint = &ctx.config.lints.explicit_join_type;
+
+        let keywords = join_keywords(node);
+        let words: Vec<String> =
+            keywords.iter().map(|keyword| keyword.text().to_ascii_lowercase()).collect();
+        let words: Vec<&str> = words.iter().map(String::as_str).collect();
+
+        let (message, fix) = match (words.as_slice(), keywords.as_slice()) {
+            (["join"], [join]) => {
+                (Self::MESSAGE.to_string(), insert_keyword(ctx, join, "INNER", "Add INNER to JOIN"))
+            }
+            ([.., side, "outer", "join"], [.., outer, join])
+                if is_outer_side(side) && lint.options.outer_keyword == OuterKeyword::Omit =>
+            {
+                let side = side.to_ascii_uppercase();
+                (
+                    format!("Use {side} JOIN instead of {side} OUTER JOIN."),
+                    Fix::single(
+                        format!("Remove OUTER from {side} OUTER JOIN"),
+                        TextEdit::delete(TextRange::new(
+                            outer.text_range().start(),
+                            join.text_range().start(),
+                        )),
+                    ),
+                )
+            }
+            ([.., side, "join"], [.., join])
+                if is_outer_side(side) && lint.options.outer_keyword == OuterKeyword::Require =>
+            {
+                let side = side.to_ascii_uppercase();
+                (
+                    format!("Use {side} OUTER JOIN instead of {side} JOIN."),
+                    insert_keyword(ctx, join, "OUTER", &format!("Add OUTER to {side} JOIN")),
+                )
+            }
+            _ => return,
+        };
+
+        let (Some(first), Some(last)) = (keywords.first(), keywords.last()) else { return };
+        diagnostics.push(
+            Diagnostic::from_text_range(
+                Self::CODE,
+                message,
+                lint.level,
+                first.text_range().cover(last.text_range()),
+            )
+            .with_fix(fix),
+        );
+    }
+}
+
+fn is_outer_side(keyword: &str) -> bool {
+    matches!(keyword, "left" | "right" | "full")
+}
+
+/// Insert `keyword` before `join`, in the keyword case of the file.
+fn insert_keyword(ctx: &LintContext<'_>, join: &SyntaxToken, keyword: &str, title: &str) -> Fix {
+    let keyword = keyword_in_case(ctx, keyword);
+    let edit = TextEdit::insert(join.text_range().start(), format!("{keyword} "));
+    Fix::single(title, edit)
+}
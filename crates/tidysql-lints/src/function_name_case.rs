@@ -0,0 +1,60 @@
+use tidysql_config::{CapitalisationConfig, Config};
+use tidysql_syntax::{DialectKind, SyntaxElement, SyntaxKind, SyntaxToken};
+
+use crate::capitalisation::{self, CaseLint, is_word};
+use crate::{Diagnostic, LintContext, Severity, TokenLint};
+
+pub(crate) struct FunctionNameCase;
+
+impl TokenLint for FunctionNameCase {
+    const CODE: &'static str = "function_name_case";
+
+    fn matches(kind: SyntaxKind) -> bool {
+        kind != SyntaxKind::QuotedIdentifier
+    }
+
+    fn level(config: &Config) -> Severity {
+        config.lints.function_name_case.level
+    }
+
+    fn check(ctx: &LintContext<'_>, token: &SyntaxToken, diagnostics: &mut Vec<Diagnostic>) {
+        capitalisation::check::<Self>(ctx, token, diagnostics);
+    }
+}
+
+impl CaseLint for FunctionNameCase {
+    const SUBJECT: &'static str = "Function names";
+    const FIX_TITLE: &'static str = "Fix function name case";
+
+    fn options(config: &Config) -> &CapitalisationConfig {
+        &config.lints.function_name_case.options
+    }
+
+    fn owns(_ctx: &LintContext<'_>, token: &SyntaxToken) -> bool {
+        is_function_name(token)
+    }
+
+    /// Most ClickHouse functions are case-sensitive.
+    fn is_case_sensitive(dialect: DialectKind, _token: &SyntaxToken) -> bool {
+        dialect == DialectKind::Clickhouse
+    }
+}
+
+/// Whether `token` is the name of a called function, leaving out the schema or project it is
+/// qualified with.
+pub(crate) fn is_function_name(token: &SyntaxToken) -> bool {
+    if token.kind() == SyntaxKind::QuotedIdentifier || !is_word(token.text()) {
+        return false;
+    }
+
+    let Some(name) = token.parent_ancestors().find(|node| node.kind() == SyntaxKind::FunctionName)
+    else {
+        return false;
+    };
+    let last = name
+        .descendants_with_tokens()
+        .filter_map(SyntaxElement::into_token)
+        .filter(|token| !token.text().trim().is_empty())
+        .last();
+    last.is_some_and(|last| last.id() == token.id())
+}
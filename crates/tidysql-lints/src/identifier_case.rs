@@ -0,0 +1,43 @@
+use tidysql_config::{CapitalisationConfig, Config};
+use tidysql_syntax::{DialectKind, SyntaxKind, SyntaxToken, is_case_sensitive_identifier};
+
+use crate::capitalisation::{self, CaseLint};
+use crate::function_name_case::is_function_name;
+use crate::type_case::is_type_name;
+use crate::{Diagnostic, LintContext, Severity, TokenLint, is_quoted_identifier};
+
+pub(crate) struct IdentifierCase;
+
+impl TokenLint for IdentifierCase {
+    const CODE: &'static str = "identifier_case";
+
+    fn matches(kind: SyntaxKind) -> bool {
+        kind == SyntaxKind::NakedIdentifier
+    }
+
+    fn level(config: &Config) -> Severity {
+        config.lints.identifier_case.level
+    }
+
+    fn check(ctx: &LintContext<'_>, token: &SyntaxToken, diagnostics: &mut Vec<Diagnostic>) {
+        capitalisation::check::<Self>(ctx, token, diagnostics);
+    }
+}
+
+impl CaseLint for IdentifierCase {
+    const SUBJECT: &'static str = "Identifiers";
+    const FIX_TITLE: &'static str = "Fix identifier case";
+
+    fn options(config: &Config) -> &CapitalisationConfig {
+        &config.lints.identifier_case.options
+    }
+
+    /// Unquoted identifiers other than function and type names, which have lints of their own.
+    fn owns(_ctx: &LintContext<'_>, token: &SyntaxToken) -> bool {
+        !is_quoted_identifier(token.text()) && !is_function_name(token) && !is_type_name(token)
+    }
+
+    fn is_case_sensitive(dialect: DialectKind, token: &SyntaxToken) -> bool {
+        is_case_sensitive_identifier(dialect, token)
+    }
+}
@@ -0,0 +1,159 @@
+use tidysql_syntax::{Fix, SyntaxElement, SyntaxKind, SyntaxNode, SyntaxToken, TextEdit};
+
+use crate::capitalisation::keyword_in_case;
+use crate::{Diagnostic, LintContext, NodeLint, Severity};
+
+pub(crate) struct JoinConditionRequired;
+
+impl NodeLint for JoinConditionRequired {
+    const CODE: &'static str = "join_condition_required";
+    const MESSAGE: &'static str = "Join tables on an ON or USING condition.";
+    const SEVERITY: Severity = Severity::Warn;
+    const TARGET: SyntaxKind = SyntaxKind::FromClause;
+
+    fn level(config: &tidysql_config::Config) -> Severity {
+        config.lints.join_condition_required.level
+    }
+
+    fn check(ctx: &LintContext<'_>, node: &SyntaxNode, diagnostics: &mut Vec<Diagnostic>) {
+        let lint = &ctx.config.lints.join_condition_required;
+
+        let mut comma = None;
+        for child in node.children_with_tokens() {
+            match child {
+                SyntaxElement::Token(token) if token.kind() == SyntaxKind::Comma => {
+                    comma = Some(token);
+                }
+                SyntaxElement::Node(expression)
+                    if expression.kind() == SyntaxKind::FromExpression =>
+                {
+                    let Some(comma) = comma.take() else { continue };
+                    if source(&expression).is_some_and(|source| is_correlated(&source)) {
+                        continue;
+                    }
+
+                    let mut diagnostic = Diagnostic::from_text_range(
+                        Self::CODE,
+                        "Comma join has no join condition; use JOIN ... ON.",
+                        lint.level,
+                        comma.text_range(),
+                    );
+                    // Without a condition to move into `ON`, the best the fix can do is to make
+                    // the cartesian product explicit, which only helps when it is allowed.
+                    if lint.options.allow_cross_join {
+                        diagnostic = diagnostic.with_fix(build_fix(ctx, &comma));
+                    }
+                    diagnostics.push(diagnostic);
+                }
+                _ => {}
+            }
+        }
+
+        for join in node.descendants().filter(|join| join.kind() == SyntaxKind::JoinClause) {
+            let clause =
+                join.ancestors().skip(1).find(|node| node.kind() == SyntaxKind::FromClause);
+            if clause.as_ref() != Some(node) {
+                continue;
+            }
+
+            let keywords = join_keywords(&join);
+            let (Some(first), Some(last)) = (keywords.first(), keywords.last()) else { continue };
+            if keywords.iter().any(|keyword| is_conditionless_join(keyword.text()))
+                || has_condition(&join)
+                || source(&join).is_some_and(|source| is_correlated(&source))
+            {
+                continue;
+            }
+
+            let is_cross =
+                keywords.iter().any(|keyword| keyword.text().eq_ignore_ascii_case("cross"));
+            if is_cross && lint.options.allow_cross_join {
+                continue;
+            }
+
+            let message = if is_cross {
+                "CROSS JOIN pairs every row with every other row; join on a condition instead."
+                    .to_string()
+            } else {
+                let words: Vec<String> =
+                    keywords.iter().map(|keyword| keyword.text().to_ascii_uppercase()).collect();
+                format!("{} has no ON or USING condition.", words.join(" "))
+            };
+
+            diagnostics.push(Diagnostic::from_text_range(
+                Self::CODE,
+                message,
+                lint.level,
+                first.text_range().cover(last.text_range()),
+            ));
+        }
+    }
+}
+
+/// The table, subquery or function that `node`, a `FROM` item or a join, reads from.
+fn source(node: &SyntaxNode) -> Option<SyntaxNode> {
+    node.children().find(|child| child.kind() == SyntaxKind::FromExpressionElement)
+}
+
+/// The keywords before the source of `join`, e.g. `LEFT OUTER JOIN`.
+pub(crate) fn join_keywords(join: &SyntaxNode) -> Vec<SyntaxToken> {
+    join.children_with_tokens()
+        .map_while(SyntaxElement::into_token)
+        .filter(|token| token.kind() == SyntaxKind::Keyword)
+        .collect()
+}
+
+/// Joins that have no condition by design: `NATURAL JOIN`, T-SQL `CROSS APPLY`, ClickHouse
+/// `ARRAY JOIN` and DuckDB `POSITIONAL JOIN`.
+fn is_conditionless_join(keyword: &str) -> bool {
+    ["natural", "apply", "array", "positional"]
+        .iter()
+        .any(|conditionless| keyword.eq_ignore_ascii_case(conditionless))
+}
+
+fn has_condition(join: &SyntaxNode) -> bool {
+    join.children_with_tokens().any(|child| match child {
+        SyntaxElement::Node(node) => node.kind() == SyntaxKind::JoinOnCondition,
+        SyntaxElement::Token(token) => {
+            token.kind() == SyntaxKind::Keyword
+                && (token.text().eq_ignore_ascii_case("on")
+                    || token.text().eq_ignore_ascii_case("using"))
+        }
+    })
+}
+
+/// Whether `source` reads from the rows of the sources before it, like `LATERAL (...)` or
+/// BigQuery `UNNEST(...)`, so that joining it without a condition is no cartesian product.
+fn is_correlated(source: &SyntaxNode) -> bool {
+    let is_lateral = source
+        .descendants_with_tokens()
+        .filter_map(SyntaxElement::into_token)
+        .find(|token| !token.text().is_empty())
+        .is_some_and(|token| {
+            token.kind() == SyntaxKind::Keyword && token.text().eq_ignore_ascii_case("lateral")
+        });
+
+    is_lateral
+        || source
+            .children()
+            .filter(|child| child.kind() == SyntaxKind::TableExpression)
+            .any(|table| table.children().any(|child| child.kind() == SyntaxKind::Function))
+}
+
+/// Replace `comma` with `CROSS JOIN`, in the keyword case of the file.
+fn build_fix(ctx: &LintContext<'_>, comma: &SyntaxToken) -> Fix {
+    let text = ctx.tree.text();
+    let range = comma.text_range();
+    let mut replacement = String::new();
+    if !text[..usize::from(range.start())].ends_with(char::is_whitespace) {
+        replacement.push(' ');
+    }
+    replacement.push_str(&keyword_in_case(ctx, "CROSS"));
+    replacement.push(' ');
+    replacement.push_str(&keyword_in_case(ctx, "JOIN"));
+    if !text[usize::from(range.end())..].starts_with(char::is_whitespace) {
+        replacement.push(' ');
+    }
+
+    Fix::single("Rewrite the comma join as CROSS JOIN", TextEdit::replace(range, replacement))
+}
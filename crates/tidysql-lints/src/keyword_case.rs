@@ -1,6 +1,10 @@
-use tidysql_config::CapitalisationPolicy;
-use tidysql_syntax::{Fix, SyntaxElement, SyntaxKind, SyntaxToken, TextEdit};
+use tidysql_config::{CapitalisationConfig, Config};
+use tidysql_syntax::{SyntaxKind, SyntaxToken};
 
+use crate::capitalisation::{self, CaseLint};
+use crate::function_name_case::FunctionNameCase;
+use crate::literal_case::LiteralCase;
+use crate::type_case::TypeCase;
 use crate::{Diagnostic, LintContext, Severity, TokenLint};
 
 pub(crate) struct KeywordCase;
@@ -12,129 +16,32 @@ impl TokenLint for KeywordCase {
         kind == SyntaxKind::Keyword
     }
 
-    fn level(config: &tidysql_config::Config) -> Severity {
+    fn level(config: &Config) -> Severity {
         config.lints.keyword_case.level
     }
 
     fn check(ctx: &LintContext<'_>, token: &SyntaxToken, diagnostics: &mut Vec<Diagnostic>) {
-        let options = &ctx.config.lints.keyword_case.options;
-        let text = token.text();
-
-        if is_ignored(text, options) {
-            return;
-        }
-
-        let policy = resolve_policy(options.policy, ctx);
-        if is_correct_case(text, policy) {
-            return;
-        }
-
-        let fixed = apply_case(text, policy);
-        let edit = TextEdit::replace(token.text_range(), fixed);
-        let fix = Fix::single("Fix keyword case", edit);
-
-        diagnostics.push(
-            Diagnostic::from_text_range(
-                Self::CODE,
-                format!("Keywords must be {}.", policy_description(policy)),
-                ctx.config.lints.keyword_case.level,
-                token.text_range(),
-            )
-            .with_fix(fix),
-        );
-    }
-}
-
-fn policy_description(policy: CapitalisationPolicy) -> &'static str {
-    match policy {
-        CapitalisationPolicy::Consistent => "consistent",
-        CapitalisationPolicy::Upper => "uppercase",
-        CapitalisationPolicy::Lower | CapitalisationPolicy::Snake => "lowercase",
-        CapitalisationPolicy::Pascal | CapitalisationPolicy::Capitalise => "capitalised",
-        CapitalisationPolicy::Camel => "camelCase",
+        capitalisation::check::<Self>(ctx, token, diagnostics);
     }
 }
 
-fn is_ignored(text: &str, options: &tidysql_config::KeywordCaseConfig) -> bool {
-    options.ignore_words.iter().any(|w| w.eq_ignore_ascii_case(text))
-        || options.ignore_words_regex.iter().any(|r| r.is_match(text))
-}
+impl CaseLint for KeywordCase {
+    const SUBJECT: &'static str = "Keywords";
+    const FIX_TITLE: &'static str = "Fix keyword case";
 
-fn resolve_policy(policy: CapitalisationPolicy, ctx: &LintContext<'_>) -> CapitalisationPolicy {
-    match policy {
-        CapitalisationPolicy::Consistent => infer_policy(ctx),
-        other => other,
+    fn options(config: &Config) -> &CapitalisationConfig {
+        &config.lints.keyword_case.options
     }
-}
-
-fn infer_policy(ctx: &LintContext<'_>) -> CapitalisationPolicy {
-    let (upper, lower) = ctx
-        .tree
-        .root()
-        .descendants_with_tokens()
-        .filter_map(|el| match el {
-            SyntaxElement::Token(t) if t.kind() == SyntaxKind::Keyword => Some(t),
-            _ => None,
-        })
-        .fold((0usize, 0usize), |(upper, lower), token| {
-            let text = token.text();
-            if is_all_upper(text) {
-                (upper + 1, lower)
-            } else if is_all_lower(text) {
-                (upper, lower + 1)
-            } else {
-                (upper, lower)
-            }
-        });
-
-    if upper >= lower { CapitalisationPolicy::Upper } else { CapitalisationPolicy::Lower }
-}
 
-fn is_correct_case(text: &str, policy: CapitalisationPolicy) -> bool {
-    match policy {
-        CapitalisationPolicy::Consistent => true,
-        CapitalisationPolicy::Upper => is_all_upper(text),
-        CapitalisationPolicy::Lower | CapitalisationPolicy::Snake | CapitalisationPolicy::Camel => {
-            is_all_lower(text)
-        }
-        CapitalisationPolicy::Pascal | CapitalisationPolicy::Capitalise => is_capitalised(text),
+    /// Keywords that name a function, a data type or a literal are left to the lint for those
+    /// when it is enabled.
+    fn owns(ctx: &LintContext<'_>, token: &SyntaxToken) -> bool {
+        !claimed_by::<FunctionNameCase>(ctx, token)
+            && !claimed_by::<TypeCase>(ctx, token)
+            && !claimed_by::<LiteralCase>(ctx, token)
     }
 }
 
-fn apply_case(text: &str, policy: CapitalisationPolicy) -> String {
-    match policy {
-        CapitalisationPolicy::Consistent => text.to_string(),
-        CapitalisationPolicy::Upper => text.to_ascii_uppercase(),
-        CapitalisationPolicy::Lower | CapitalisationPolicy::Snake | CapitalisationPolicy::Camel => {
-            text.to_ascii_lowercase()
-        }
-        CapitalisationPolicy::Pascal | CapitalisationPolicy::Capitalise => capitalise(text),
-    }
-}
-
-fn is_all_upper(text: &str) -> bool {
-    !text.bytes().any(|b| b.is_ascii_lowercase())
-}
-
-fn is_all_lower(text: &str) -> bool {
-    !text.bytes().any(|b| b.is_ascii_uppercase())
-}
-
-fn is_capitalised(text: &str) -> bool {
-    let mut bytes = text.bytes();
-    let first_ok = bytes.next().is_none_or(|b| b.is_ascii_uppercase());
-    let rest_ok = !bytes.any(|b| b.is_ascii_uppercase());
-    first_ok && rest_ok
-}
-
-fn capitalise(text: &str) -> String {
-    let mut result = String::with_capacity(text.len());
-    let mut bytes = text.bytes();
-    if let Some(first) = bytes.next() {
-        result.push(first.to_ascii_uppercase() as char);
-    }
-    for b in bytes {
-        result.push(b.to_ascii_lowercase() as char);
-    }
-    result
+fn claimed_by<L: CaseLint>(ctx: &LintContext<'_>, token: &SyntaxToken) -> bool {
+    L::level(ctx.config) != Severity::Allow && L::matches(token.kind()) && L::owns(ctx, token)
 }
@@ -6,9 +6,24 @@ use tidysql_syntax::{
     DialectKind, Fix, SyntaxElement, SyntaxKind, SyntaxNode, SyntaxToken, SyntaxTree, TextRange,
 };
 
+mod ambiguous_column_reference;
+mod capitalisation;
+mod consistent_aliasing;
 mod disallow_names;
+mod duplicate_alias;
+mod explicit_join_type;
 mod explicit_union;
+mod function_name_case;
+mod identifier_case;
+mod join_condition_required;
 mod keyword_case;
+mod literal_case;
+mod null_comparison;
+mod select_star;
+mod sources;
+mod type_case;
+mod unused_cte;
+mod unused_table_alias;
 
 #[derive(Debug, Clone)]
 pub struct Diagnostic {
@@ -85,12 +100,26 @@ pub fn run(dialect: DialectKind, tree: &SyntaxTree, config: &Config) -> Vec<Diag
 }
 
 fn run_node_lints(ctx: &LintContext<'_>, node: &SyntaxNode, diagnostics: &mut Vec<Diagnostic>) {
+    run_node_lint::<ambiguous_column_reference::AmbiguousColumnReference>(ctx, node, diagnostics);
+    run_node_lint::<consistent_aliasing::ConsistentAliasing>(ctx, node, diagnostics);
+    run_node_lint::<duplicate_alias::DuplicateAlias>(ctx, node, diagnostics);
+    run_node_lint::<duplicate_alias::DuplicateCteName>(ctx, node, diagnostics);
+    run_node_lint::<explicit_join_type::ExplicitJoinType>(ctx, node, diagnostics);
     run_node_lint::<explicit_union::ExplicitUnion>(ctx, node, diagnostics);
+    run_node_lint::<join_condition_required::JoinConditionRequired>(ctx, node, diagnostics);
+    run_node_lint::<null_comparison::NullComparison>(ctx, node, diagnostics);
+    run_node_lint::<select_star::SelectStar>(ctx, node, diagnostics);
+    run_node_lint::<unused_cte::UnusedCte>(ctx, node, diagnostics);
+    run_node_lint::<unused_table_alias::UnusedTableAlias>(ctx, node, diagnostics);
 }
 
 fn run_token_lints(ctx: &LintContext<'_>, token: &SyntaxToken, diagnostics: &mut Vec<Diagnostic>) {
     run_token_lint::<disallow_names::DisallowNames>(ctx, token, diagnostics);
+    run_token_lint::<function_name_case::FunctionNameCase>(ctx, token, diagnostics);
+    run_token_lint::<identifier_case::IdentifierCase>(ctx, token, diagnostics);
     run_token_lint::<keyword_case::KeywordCase>(ctx, token, diagnostics);
+    run_token_lint::<literal_case::LiteralCase>(ctx, token, diagnostics);
+    run_token_lint::<type_case::TypeCase>(ctx, token, diagnostics);
 }
 
 fn run_node_lint<L: NodeLint>(
@@ -121,6 +150,17 @@ fn run_token_lint<L: TokenLint>(
     }
 }
 
+/// The name inside `"..."`, `` `...` `` or `[...]` quotes, or `text` itself when it is not quoted.
+pub(crate) fn strip_identifier_quotes(text: &str) -> &str {
+    if is_quoted_identifier(text) { &text[1..text.len() - 1] } else { text }
+}
+
+pub(crate) fn is_quoted_identifier(text: &str) -> bool {
+    let bytes = text.as_bytes();
+    bytes.len() >= 2
+        && matches!((bytes[0], bytes[bytes.len() - 1]), (b'"', b'"') | (b'`', b'`') | (b'[', b']'))
+}
+
 fn text_range_to_range(range: TextRange) -> Range<usize> {
     range.start().into()..range.end().into()
 }
@@ -0,0 +1,45 @@
+use tidysql_config::{CapitalisationConfig, Config};
+use tidysql_syntax::{SyntaxKind, SyntaxToken};
+
+use crate::capitalisation::{self, CaseLint};
+use crate::{Diagnostic, LintContext, Severity, TokenLint};
+
+pub(crate) struct LiteralCase;
+
+impl TokenLint for LiteralCase {
+    const CODE: &'static str = "literal_case";
+
+    fn matches(kind: SyntaxKind) -> bool {
+        matches!(kind, SyntaxKind::NullLiteral | SyntaxKind::BooleanLiteral | SyntaxKind::Keyword)
+    }
+
+    fn level(config: &Config) -> Severity {
+        config.lints.literal_case.level
+    }
+
+    fn check(ctx: &LintContext<'_>, token: &SyntaxToken, diagnostics: &mut Vec<Diagnostic>) {
+        capitalisation::check::<Self>(ctx, token, diagnostics);
+    }
+}
+
+impl CaseLint for LiteralCase {
+    const SUBJECT: &'static str = "Literals";
+    const FIX_TITLE: &'static str = "Fix literal case";
+
+    fn options(config: &Config) -> &CapitalisationConfig {
+        &config.lints.literal_case.options
+    }
+
+    /// `NULL`, `TRUE` and `FALSE`, whether the parser marks them as literals or as keywords
+    /// inside a literal.
+    fn owns(_ctx: &LintContext<'_>, token: &SyntaxToken) -> bool {
+        match token.kind() {
+            SyntaxKind::NullLiteral | SyntaxKind::BooleanLiteral => true,
+            SyntaxKind::Keyword => matches!(
+                token.parent().kind(),
+                SyntaxKind::NullLiteral | SyntaxKind::BooleanLiteral
+            ),
+            _ => false,
+        }
+    }
+}
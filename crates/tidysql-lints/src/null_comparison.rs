@@ -0,0 +1,158 @@
+use tidysql_syntax::{
+    Fix, SyntaxElement, SyntaxKind, SyntaxNode, SyntaxToken, TextEdit, TextRange,
+};
+
+use crate::capitalisation::keyword_in_case;
+use crate::{Diagnostic, LintContext, NodeLint, Severity};
+
+pub(crate) struct NullComparison;
+
+impl NodeLint for NullComparison {
+    const CODE: &'static str = "null_comparison";
+    const MESSAGE: &'static str = "Use IS NULL or IS NOT NULL to compare with NULL.";
+    const SEVERITY: Severity = Severity::Warn;
+    const TARGET: SyntaxKind = SyntaxKind::ComparisonOperator;
+
+    fn level(config: &tidysql_config::Config) -> Severity {
+        config.lints.null_comparison.level
+    }
+
+    fn check(ctx: &LintContext<'_>, node: &SyntaxNode, diagnostics: &mut Vec<Diagnostic>) {
+        // `<>` may come as two tokens, `<` and `>`.
+        let operator_tokens = tokens(&SyntaxElement::Node(node.clone()));
+        let operator: String = operator_tokens.iter().map(SyntaxToken::text).collect();
+        let negated = match operator.as_str() {
+            "=" | "==" => false,
+            "!=" | "<>" => true,
+            _ => return,
+        };
+        let (Some(first), Some(last)) = (operator_tokens.first(), operator_tokens.last()) else {
+            return;
+        };
+        let operator_range = first.text_range().cover(last.text_range());
+
+        // Outside expressions `=` assigns, as in `UPDATE ... SET x = NULL`.
+        let Some(parent) = node.parent().filter(|parent| parent.kind() == SyntaxKind::Expression)
+        else {
+            return;
+        };
+        let elements: Vec<SyntaxElement> =
+            parent.children_with_tokens().filter(|element| !tokens(element).is_empty()).collect();
+        let Some(position) = elements.iter().position(|element| element.as_node() == Some(node))
+        else {
+            return;
+        };
+        let left = position.checked_sub(1).and_then(|index| elements.get(index));
+        let right = elements.get(position + 1);
+
+        let (null, fix) = if let Some(null) = right.and_then(null_literal) {
+            let fix = replace_operator(ctx, operator_range, &null, negated);
+            (null, Some(fix))
+        } else if let Some(null) = left.and_then(null_literal) {
+            // Moving the other operand in front of `IS NULL` is only safe when nothing else in
+            // the expression binds to it.
+            let fix = match (elements.len(), right) {
+                (3, Some(operand)) => swap_operands(ctx, &null, operand, negated),
+                _ => None,
+            };
+            (null, fix)
+        } else {
+            return;
+        };
+
+        let message = if negated {
+            format!("Use IS NOT NULL instead of comparing with `{operator}` to NULL.")
+        } else {
+            format!("Use IS NULL instead of comparing with `{operator}` to NULL.")
+        };
+        let mut diagnostic = Diagnostic::from_text_range(
+            Self::CODE,
+            message,
+            ctx.config.lints.null_comparison.level,
+            operator_range.cover(null.text_range()),
+        );
+        if let Some(fix) = fix {
+            diagnostic = diagnostic.with_fix(fix);
+        }
+        diagnostics.push(diagnostic);
+    }
+}
+
+/// The tokens of `element` that are written in the source, leaving out the empty indentation
+/// markers.
+fn tokens(element: &SyntaxElement) -> Vec<SyntaxToken> {
+    match element {
+        SyntaxElement::Token(token) => {
+            if token.text().is_empty() {
+                Vec::new()
+            } else {
+                vec![token.clone()]
+            }
+        }
+        SyntaxElement::Node(node) => node
+            .descendants_with_tokens()
+            .filter_map(SyntaxElement::into_token)
+            .filter(|token| !token.text().is_empty())
+            .collect(),
+    }
+}
+
+/// The `NULL` that `element` consists of, if it is one.
+fn null_literal(element: &SyntaxElement) -> Option<SyntaxToken> {
+    match tokens(element).as_slice() {
+        [token]
+            if matches!(token.kind(), SyntaxKind::NullLiteral | SyntaxKind::Keyword)
+                && token.text().eq_ignore_ascii_case("null") =>
+        {
+            Some(token.clone())
+        }
+        _ => None,
+    }
+}
+
+/// `IS ` or `IS NOT ` in the keyword case of the file.
+fn is_keywords(ctx: &LintContext<'_>, negated: bool) -> String {
+    let mut keywords = format!("{} ", keyword_in_case(ctx, "IS"));
+    if negated {
+        keywords.push_str(&keyword_in_case(ctx, "NOT"));
+        keywords.push(' ');
+    }
+    keywords
+}
+
+/// Rewrite `x = NULL` into `x IS NULL`.
+fn replace_operator(
+    ctx: &LintContext<'_>,
+    operator: TextRange,
+    null: &SyntaxToken,
+    negated: bool,
+) -> Fix {
+    let mut replacement = String::new();
+    if !ctx.tree.text()[..usize::from(operator.start())].ends_with(char::is_whitespace) {
+        replacement.push(' ');
+    }
+    replacement.push_str(&is_keywords(ctx, negated));
+
+    let range = TextRange::new(operator.start(), null.text_range().start());
+    Fix::single(fix_title(negated), TextEdit::replace(range, replacement))
+}
+
+/// Rewrite `NULL = x` into `x IS NULL`.
+fn swap_operands(
+    ctx: &LintContext<'_>,
+    null: &SyntaxToken,
+    operand: &SyntaxElement,
+    negated: bool,
+) -> Option<Fix> {
+    let operand = tokens(operand);
+    let (first, last) = (operand.first()?, operand.last()?);
+    let operand = &ctx.tree.text()[first.text_range().cover(last.text_range())];
+
+    let replacement = format!("{operand} {}{}", is_keywords(ctx, negated), null.text());
+    let range = TextRange::new(null.text_range().start(), last.text_range().end());
+    Some(Fix::single(fix_title(negated), TextEdit::replace(range, replacement)))
+}
+
+fn fix_title(negated: bool) -> &'static str {
+    if negated { "Rewrite as IS NOT NULL" } else { "Rewrite as IS NULL" }
+}
@@ -0,0 +1,117 @@
+use tidysql_syntax::{DialectKind, SyntaxElement, SyntaxKind, SyntaxNode};
+
+use crate::{Diagnostic, LintContext, NodeLint, Severity};
+
+pub(crate) struct SelectStar;
+
+impl NodeLint for SelectStar {
+    const CODE: &'static str = "select_star";
+    const MESSAGE: &'static str = "Avoid SELECT *; list the columns explicitly.";
+    const SEVERITY: Severity = Severity::Warn;
+    const TARGET: SyntaxKind = SyntaxKind::WildcardExpression;
+
+    fn level(config: &tidysql_config::Config) -> Severity {
+        config.lints.select_star.level
+    }
+
+    fn check(ctx: &LintContext<'_>, node: &SyntaxNode, diagnostics: &mut Vec<Diagnostic>) {
+        let options = &ctx.config.lints.select_star.options;
+
+        let Some(parent) = node.parent() else { return };
+        if parent.kind() != SyntaxKind::SelectClauseElement {
+            // Wildcards only pick columns in a select list; elsewhere `COUNT(*)` is the one
+            // worth reporting, and it is not a `SELECT *`.
+            if !options.allow_count && is_count_argument(node) {
+                diagnostics.push(Diagnostic::from_text_range(
+                    Self::CODE,
+                    "Avoid COUNT(*); count a column instead.",
+                    ctx.config.lints.select_star.level,
+                    node.text_range(),
+                ));
+            }
+            return;
+        }
+        if let Some(select) =
+            node.ancestors().find(|node| node.kind() == SyntaxKind::SelectStatement)
+        {
+            if options.allow_exists && is_exists_subquery(&select) {
+                return;
+            }
+            if options.allow_outermost && is_outermost(&select) {
+                return;
+            }
+        }
+
+        let message = match modifier(ctx.dialect, node) {
+            Some(modifier) => format!(
+                "Avoid SELECT * {modifier}, which still picks up new columns; list the columns \
+                 explicitly."
+            ),
+            None => Self::MESSAGE.to_string(),
+        };
+
+        diagnostics.push(Diagnostic::from_text_range(
+            Self::CODE,
+            message,
+            ctx.config.lints.select_star.level,
+            node.text_range(),
+        ));
+    }
+}
+
+/// The keyword of a modifier such as BigQuery `* EXCEPT (...)` or Snowflake `* EXCLUDE (...)`
+/// following the wildcard, among the ones `dialect` supports.
+fn modifier(dialect: DialectKind, node: &SyntaxNode) -> Option<String> {
+    let supported: &[&str] = match dialect {
+        DialectKind::Bigquery => &["except", "replace"],
+        DialectKind::Clickhouse => &["except", "replace", "apply"],
+        DialectKind::Databricks | DialectKind::Sparksql => &["except"],
+        DialectKind::Duckdb => &["exclude", "replace"],
+        DialectKind::Snowflake => &["exclude", "replace", "rename", "ilike"],
+        _ => &[],
+    };
+
+    node.descendants_with_tokens()
+        .filter_map(SyntaxElement::into_token)
+        .filter(|token| token.kind() == SyntaxKind::Keyword)
+        .find(|token| supported.iter().any(|keyword| token.text().eq_ignore_ascii_case(keyword)))
+        .map(|token| token.text().to_ascii_uppercase())
+}
+
+fn is_count_argument(node: &SyntaxNode) -> bool {
+    node.ancestors().find(|node| node.kind() == SyntaxKind::Function).is_some_and(|function| {
+        function.children().any(|child| {
+            child.kind() == SyntaxKind::FunctionName
+                && child.last_token().text().eq_ignore_ascii_case("count")
+        })
+    })
+}
+
+/// Whether `select` is the query of `EXISTS (...)`, possibly one branch of a set expression.
+fn is_exists_subquery(select: &SyntaxNode) -> bool {
+    let Some(bracketed) = select.ancestors().skip(1).find(|node| {
+        !matches!(node.kind(), SyntaxKind::SetExpression | SyntaxKind::WithCompoundStatement)
+    }) else {
+        return false;
+    };
+    if bracketed.kind() != SyntaxKind::Bracketed {
+        return false;
+    }
+
+    let mut previous = bracketed.first_token().prev_token();
+    while let Some(token) = previous {
+        if !token.text().is_empty() {
+            return token.kind() == SyntaxKind::Keyword
+                && token.text().eq_ignore_ascii_case("exists");
+        }
+        previous = token.prev_token();
+    }
+    false
+}
+
+/// Whether `select` produces the result of its statement rather than feeding another query.
+fn is_outermost(select: &SyntaxNode) -> bool {
+    select.ancestors().skip(1).take_while(|node| node.kind() != SyntaxKind::Statement).all(|node| {
+        !matches!(node.kind(), SyntaxKind::Bracketed | SyntaxKind::CommonTableExpression)
+    })
+}
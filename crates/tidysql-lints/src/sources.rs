@@ -0,0 +1,71 @@
+use tidysql_syntax::{SyntaxElement, SyntaxKind, SyntaxNode, SyntaxToken};
+
+/// A table, view or subquery a `SELECT` reads from, in its `FROM` clause or a join.
+pub(crate) struct Source {
+    /// The last part of the table name, e.g. `orders` in `sales.orders`. `None` for subqueries
+    /// and table functions.
+    pub(crate) table: Option<SyntaxToken>,
+    pub(crate) alias: Option<SyntaxToken>,
+}
+
+impl Source {
+    /// The name the rest of the query refers to this source by: its alias when it has one, its
+    /// table name otherwise.
+    pub(crate) fn name(&self) -> Option<&SyntaxToken> {
+        self.alias.as_ref().or(self.table.as_ref())
+    }
+
+    /// Whether `qualifier` refers to this source.
+    pub(crate) fn is_named(&self, qualifier: &str) -> bool {
+        self.name().is_some_and(|name| same_name(name.text(), qualifier))
+    }
+}
+
+/// The sources of `select`, leaving out the ones of the subqueries it contains.
+pub(crate) fn sources(select: &SyntaxNode) -> Vec<Source> {
+    let Some(from) = select.children().find(|child| child.kind() == SyntaxKind::FromClause) else {
+        return Vec::new();
+    };
+
+    from.descendants()
+        .filter(|node| node.kind() == SyntaxKind::FromExpressionElement)
+        .filter(|node| is_in_scope(select, node))
+        .map(|element| {
+            let table = element
+                .children()
+                .find(|child| child.kind() == SyntaxKind::TableExpression)
+                .and_then(|expression| {
+                    expression.children().find(|child| child.kind() == SyntaxKind::TableReference)
+                })
+                .and_then(|reference| name_parts(&reference).pop());
+            let alias = element
+                .children()
+                .find(|child| child.kind() == SyntaxKind::AliasExpression)
+                .and_then(|alias| name_parts(&alias).pop());
+            Source { table, alias }
+        })
+        .collect()
+}
+
+/// Whether `node` belongs to `select` itself rather than to a subquery nested in it.
+pub(crate) fn is_in_scope(select: &SyntaxNode, node: &SyntaxNode) -> bool {
+    node.ancestors()
+        .skip(1)
+        .find(|ancestor| ancestor.kind() == SyntaxKind::SelectStatement)
+        .is_some_and(|ancestor| ancestor.text_range() == select.text_range())
+}
+
+/// The identifiers of a dotted name such as a column or table reference, in order.
+pub(crate) fn name_parts(node: &SyntaxNode) -> Vec<SyntaxToken> {
+    node.children_with_tokens()
+        .filter_map(SyntaxElement::into_token)
+        .filter(|token| {
+            matches!(token.kind(), SyntaxKind::NakedIdentifier | SyntaxKind::QuotedIdentifier)
+        })
+        .collect()
+}
+
+/// Whether two identifiers name the same thing, ignoring case and quotes.
+pub(crate) fn same_name(left: &str, right: &str) -> bool {
+    crate::strip_identifier_quotes(left).eq_ignore_ascii_case(crate::strip_identifier_quotes(right))
+}
@@ -0,0 +1,49 @@
+use tidysql_config::{CapitalisationConfig, Config};
+use tidysql_syntax::{DialectKind, SyntaxKind, SyntaxToken};
+
+use crate::capitalisation::{self, CaseLint, is_word};
+use crate::{Diagnostic, LintContext, Severity, TokenLint};
+
+pub(crate) struct TypeCase;
+
+impl TokenLint for TypeCase {
+    const CODE: &'static str = "type_case";
+
+    fn matches(kind: SyntaxKind) -> bool {
+        kind != SyntaxKind::QuotedIdentifier
+    }
+
+    fn level(config: &Config) -> Severity {
+        config.lints.type_case.level
+    }
+
+    fn check(ctx: &LintContext<'_>, token: &SyntaxToken, diagnostics: &mut Vec<Diagnostic>) {
+        capitalisation::check::<Self>(ctx, token, diagnostics);
+    }
+}
+
+impl CaseLint for TypeCase {
+    const SUBJECT: &'static str = "Data types";
+    const FIX_TITLE: &'static str = "Fix data type case";
+
+    fn options(config: &Config) -> &CapitalisationConfig {
+        &config.lints.type_case.options
+    }
+
+    fn owns(_ctx: &LintContext<'_>, token: &SyntaxToken) -> bool {
+        is_type_name(token)
+    }
+
+    /// ClickHouse data types such as `UInt64` are case-sensitive.
+    fn is_case_sensitive(dialect: DialectKind, _token: &SyntaxToken) -> bool {
+        dialect == DialectKind::Clickhouse
+    }
+}
+
+/// Whether `token` is a word of a data type, such as `varchar` or `precision` in
+/// `double precision`.
+pub(crate) fn is_type_name(token: &SyntaxToken) -> bool {
+    token.kind() != SyntaxKind::QuotedIdentifier
+        && is_word(token.text())
+        && token.parent_ancestors().any(|node| node.kind() == SyntaxKind::DataType)
+}
@@ -0,0 +1,152 @@
+use tidysql_syntax::{
+    Fix, SyntaxElement, SyntaxKind, SyntaxNode, SyntaxToken, TextEdit, TextRange, TextSize,
+};
+
+use crate::sources::{name_parts, same_name};
+use crate::{Diagnostic, LintContext, NodeLint, Severity};
+
+pub(crate) struct UnusedCte;
+
+impl NodeLint for UnusedCte {
+    const CODE: &'static str = "unused_cte";
+    const MESSAGE: &'static str = "Remove CTEs that nothing references.";
+    const SEVERITY: Severity = Severity::Warn;
+    const TARGET: SyntaxKind = SyntaxKind::WithCompoundStatement;
+
+    fn level(config: &tidysql_config::Config) -> Severity {
+        config.lints.unused_cte.level
+    }
+
+    fn check(ctx: &LintContext<'_>, node: &SyntaxNode, diagnostics: &mut Vec<Diagnostic>) {
+        let ctes: Vec<SyntaxNode> = node
+            .children()
+            .filter(|child| child.kind() == SyntaxKind::CommonTableExpression)
+            .collect();
+        let names: Vec<Option<SyntaxToken>> =
+            ctes.iter().map(|cte| name_parts(cte).into_iter().next()).collect();
+        let live = live_ctes(node, &ctes, &names);
+        let any_live = live.iter().any(|live| *live);
+
+        let mut whole_clause_fixed = false;
+        for (index, name) in names.iter().enumerate() {
+            if live[index] {
+                continue;
+            }
+            let Some(name) = name else { continue };
+
+            let fix = if any_live {
+                Some(Fix::single(
+                    "Remove the unused CTE",
+                    TextEdit::delete(removal_range(ctx.tree.text(), &ctes, &live, index)),
+                ))
+            } else if !whole_clause_fixed {
+                // Dropping the CTEs one by one would leave a bare `WITH`, so the first report
+                // removes the whole clause and the others carry no fix that would overlap it.
+                whole_clause_fixed = true;
+                Some(Fix::single(
+                    "Remove the unused WITH clause",
+                    TextEdit::delete(with_clause_range(ctx.tree.text(), node)),
+                ))
+            } else {
+                None
+            };
+
+            let mut diagnostic = Diagnostic::from_text_range(
+                Self::CODE,
+                format!("CTE `{}` is never used.", name.text()),
+                ctx.config.lints.unused_cte.level,
+                name.text_range(),
+            );
+            if let Some(fix) = fix {
+                diagnostic = diagnostic.with_fix(fix);
+            }
+            diagnostics.push(diagnostic);
+        }
+    }
+}
+
+/// Which of `ctes` the query of `with` uses, directly or through the CTEs it uses.
+fn live_ctes(with: &SyntaxNode, ctes: &[SyntaxNode], names: &[Option<SyntaxToken>]) -> Vec<bool> {
+    let mut live = vec![false; ctes.len()];
+    let mut pending: Vec<usize> = with
+        .children()
+        .filter(|child| child.kind() != SyntaxKind::CommonTableExpression)
+        .flat_map(|query| referenced_ctes(&query, names))
+        .collect();
+
+    while let Some(index) = pending.pop() {
+        if !live[index] {
+            live[index] = true;
+            pending.extend(referenced_ctes(&ctes[index], names));
+        }
+    }
+
+    live
+}
+
+/// The indexes of the CTEs in `names` that the tables read in `node` refer to.
+fn referenced_ctes(node: &SyntaxNode, names: &[Option<SyntaxToken>]) -> Vec<usize> {
+    node.descendants()
+        .filter(|node| node.kind() == SyntaxKind::TableReference)
+        .filter_map(|reference| match name_parts(&reference).as_slice() {
+            // `schema.name` is a table even when a CTE is called `name`.
+            [table] => names.iter().position(|name| {
+                name.as_ref().is_some_and(|name| same_name(name.text(), table.text()))
+            }),
+            _ => None,
+        })
+        .collect()
+}
+
+/// The text to delete to remove `ctes[index]` together with one of the commas around it. Unused
+/// CTEs before a used one take the comma after them, the others the comma before them, so that
+/// the fixes of all the unused CTEs of a clause can be applied together. Comments after the
+/// comma belong to the CTE that follows and are kept.
+fn removal_range(text: &str, ctes: &[SyntaxNode], live: &[bool], index: usize) -> TextRange {
+    let cte = &ctes[index];
+    if live[index + 1..].iter().any(|live| *live) {
+        let gap = TextRange::new(end(cte), start(&ctes[index + 1]));
+        // Walk the tokens rather than the text, which may hold commas inside comments.
+        let comma = std::iter::successors(cte.last_token().next_token(), SyntaxToken::next_token)
+            .take_while(|token| token.text_range().start() < gap.end())
+            .find(|token| token.kind() == SyntaxKind::Comma);
+        let after_comma = comma.map_or(gap.start(), |comma| comma.text_range().end());
+        TextRange::new(start(cte), skip_whitespace(text, after_comma).min(gap.end()))
+    } else {
+        TextRange::new(end(&ctes[index - 1]), end(cte))
+    }
+}
+
+/// The text from `WITH` up to the query that follows the CTEs, or up to the comments before
+/// that query.
+fn with_clause_range(text: &str, with: &SyntaxNode) -> TextRange {
+    let last_cte =
+        with.children().filter(|child| child.kind() == SyntaxKind::CommonTableExpression).last();
+    let end = match last_cte {
+        Some(cte) => skip_whitespace(text, end(&cte)),
+        None => end(with),
+    };
+    TextRange::new(start(with), end)
+}
+
+/// The offset of the first character at or after `offset` that is not whitespace.
+fn skip_whitespace(text: &str, offset: TextSize) -> TextSize {
+    let rest = &text[usize::from(offset)..];
+    offset + TextSize::of(&rest[..rest.len() - rest.trim_start().len()])
+}
+
+/// Where the first token of `node` starts, leaving out the whitespace and comments before it.
+fn start(node: &SyntaxNode) -> TextSize {
+    tokens(node).next().map_or(node.text_range().start(), |token| token.text_range().start())
+}
+
+/// Where the last token of `node` ends, leaving out the whitespace and comments after it.
+fn end(node: &SyntaxNode) -> TextSize {
+    tokens(node).last().map_or(node.text_range().end(), |token| token.text_range().end())
+}
+
+fn tokens(node: &SyntaxNode) -> impl Iterator<Item = SyntaxToken> {
+    node.descendants_with_tokens()
+        .filter_map(SyntaxElement::into_token)
+        .filter(|token| !token.text().is_empty())
+}
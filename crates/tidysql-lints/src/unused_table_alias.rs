@@ -0,0 +1,61 @@
+use tidysql_syntax::{SyntaxKind, SyntaxNode, SyntaxToken};
+
+use crate::sources::{name_parts, same_name, sources};
+use crate::{Diagnostic, LintContext, NodeLint, Severity};
+
+pub(crate) struct UnusedTableAlias;
+
+impl NodeLint for UnusedTableAlias {
+    const CODE: &'static str = "unused_table_alias";
+    const MESSAGE: &'static str = "Remove table aliases that nothing refers to.";
+    const SEVERITY: Severity = Severity::Warn;
+    const TARGET: SyntaxKind = SyntaxKind::SelectStatement;
+
+    fn level(config: &tidysql_config::Config) -> Severity {
+        config.lints.unused_table_alias.level
+    }
+
+    fn check(ctx: &LintContext<'_>, node: &SyntaxNode, diagnostics: &mut Vec<Diagnostic>) {
+        for source in sources(node) {
+            // Subqueries and table functions need their alias to be read from at all, so only
+            // the aliases of named tables can go unused.
+            let (Some(_), Some(alias)) = (&source.table, &source.alias) else { continue };
+            if is_referenced(node, alias) {
+                continue;
+            }
+
+            diagnostics.push(Diagnostic::from_text_range(
+                Self::CODE,
+                format!("Table alias `{}` is never used.", alias.text()),
+                ctx.config.lints.unused_table_alias.level,
+                alias.text_range(),
+            ));
+        }
+    }
+}
+
+/// Whether a column qualifier, `alias.*` or a BigQuery path like `FROM alias.items` anywhere in
+/// `select`, including its subqueries, names `alias`.
+fn is_referenced(select: &SyntaxNode, alias: &SyntaxToken) -> bool {
+    select.descendants().any(|node| {
+        let parts = name_parts(&node);
+        match node.kind() {
+            // The last part of a column is the column itself, unless it is all there is: a
+            // bare alias reads the whole row, or the whole struct in BigQuery.
+            SyntaxKind::ColumnReference => {
+                let qualifiers = match parts.as_slice() {
+                    [_] => &parts[..],
+                    _ => &parts[..parts.len().saturating_sub(1)],
+                };
+                qualifiers.iter().any(|part| same_name(part.text(), alias.text()))
+            }
+            SyntaxKind::WildcardIdentifier => {
+                parts.iter().any(|part| same_name(part.text(), alias.text()))
+            }
+            SyntaxKind::TableReference => {
+                parts.len() > 1 && same_name(parts[0].text(), alias.text())
+            }
+            _ => false,
+        }
+    })
+}
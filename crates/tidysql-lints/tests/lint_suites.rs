@@ -1,7 +1,7 @@
 use std::path::Path;
 
 use serde::Deserialize;
-use tidysql_config::{Config, Dialect};
+use tidysql_config::{Config, Dialect, LintName, Lints};
 use tidysql_lints::Severity;
 use tidysql_syntax::{DialectKind, EditError, apply_edits};
 
@@ -44,6 +44,55 @@ fn run_case(path: &Path, input: String) -> datatest_stable::Result<()> {
         run_single_case(path, case_index, case)?;
     }
 
+    check_default_off(path, &suite)
+}
+
+/// Most lints stay quiet until a config turns them on. When the suite is named after one of
+/// them, every case that expects it is run again at its default level and must report nothing.
+fn check_default_off(path: &Path, suite: &LintSuite) -> datatest_stable::Result<()> {
+    let Some(lint) = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .and_then(|stem| stem.parse::<LintName>().ok())
+    else {
+        return Ok(());
+    };
+    let default_level = *Lints::default().level_mut(lint);
+    if default_level != tidysql_config::Severity::Allow {
+        return Ok(());
+    }
+
+    let mut checked = 0;
+    for (case_index, case) in suite.cases.iter().enumerate() {
+        if !case.expect.iter().any(|expected| expected.code == lint.as_str()) {
+            continue;
+        }
+
+        let label = case_label(case, case_index);
+        let mut config = case.config.clone();
+        *config.lints.level_mut(lint) = default_level;
+        let dialect = config_dialect(&config);
+        let tree = tidysql_syntax::parse(&case.sql, dialect)
+            .map_err(|error| format!("{label}: {error}"))?;
+        let diagnostics = tidysql_lints::run(dialect, &tree, &config);
+
+        if let Some(diagnostic) =
+            diagnostics.iter().find(|diagnostic| diagnostic.code == lint.as_str())
+        {
+            return Err(format!(
+                "{label}: {} is off by default but reported \"{}\"",
+                lint.as_str(),
+                diagnostic.message
+            )
+            .into());
+        }
+        checked += 1;
+    }
+
+    if checked == 0 {
+        return Err(format!("no case in {} expects {}", path.display(), lint.as_str()).into());
+    }
+
     Ok(())
 }
 
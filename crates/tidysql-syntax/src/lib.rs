@@ -1472,6 +1472,18 @@ impl fmt::Display for ParseError {
 
 impl std::error::Error for ParseError {}
 
+/// Whether changing the case of the unquoted identifier `token` can change what it refers to in
+/// `dialect`. ClickHouse identifiers are case-sensitive, and so are MySQL and BigQuery table names.
+pub fn is_case_sensitive_identifier(dialect: DialectKind, token: &SyntaxToken) -> bool {
+    match dialect {
+        DialectKind::Clickhouse => true,
+        DialectKind::Mysql | DialectKind::Bigquery => {
+            token.parent_ancestors().any(|node| node.kind() == SyntaxKind::TableReference)
+        }
+        _ => false,
+    }
+}
+
 pub fn parse(sql: &str, dialect_kind: DialectKind) -> Result<SyntaxTree, ParseError> {
     let dialect = kind_to_dialect(&dialect_kind).ok_or(ParseError::UnknownDialect(dialect_kind))?;
     let lexer = Lexer::from(&dialect);
@@ -89,8 +89,8 @@ impl Workspace {
             }
         };
 
-        let formatted = tidysql::format_with_config(source, &config);
-        Ok(formatted)
+        tidysql::format_with_config(source, &config)
+            .map_err(|error| JsValue::from_str(&error.to_string()))
     }
 
     pub fn fix_with_config(&self, source: &str, config_toml: &str) -> Result<String, JsValue> {
@@ -0,0 +1,83 @@
+use std::fmt::Write;
+
+use similar::{ChangeTag, TextDiff};
+
+const BOLD: &str = "\x1b[1m";
+const RED: &str = "\x1b[31m";
+const GREEN: &str = "\x1b[32m";
+const CYAN: &str = "\x1b[36m";
+const RESET: &str = "\x1b[0m";
+
+/// Render a unified diff from `old` to `new`, with ANSI colors when `styled` is set.
+pub(crate) fn unified_diff(path: &str, old: &str, new: &str, styled: bool) -> String {
+    let paint = |style: &str, line: &str| {
+        if styled { format!("{style}{line}{RESET}") } else { line.to_string() }
+    };
+
+    let diff = TextDiff::from_lines(old, new);
+    let mut output = String::new();
+    let _ = writeln!(output, "{}", paint(BOLD, &format!("--- {path}")));
+    let _ = writeln!(output, "{}", paint(BOLD, &format!("+++ {path}")));
+
+    for hunk in diff.unified_diff().iter_hunks() {
+        let _ = writeln!(output, "{}", paint(CYAN, &hunk.header().to_string()));
+
+        for change in hunk.iter_changes() {
+            let (sign, style) = match change.tag() {
+                ChangeTag::Delete => ('-', Some(RED)),
+                ChangeTag::Insert => ('+', Some(GREEN)),
+                ChangeTag::Equal => (' ', None),
+            };
+            let line = format!("{sign}{}", change.value().trim_end_matches('\n'));
+            let line = match style {
+                Some(style) => paint(style, &line),
+                None => line,
+            };
+            let _ = writeln!(output, "{line}");
+
+            if change.missing_newline() {
+                let _ = writeln!(output, "\\ No newline at end of file");
+            }
+        }
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_a_header_for_each_hunk() {
+        let old = "a\nb\nc\nd\ne\nf\ng\nh\ni\nj\nk\nl\n";
+        let new = "A\nb\nc\nd\ne\nf\ng\nh\ni\nj\nk\nL\n";
+
+        assert_eq!(
+            unified_diff("query.sql", old, new, false),
+            "--- query.sql\n+++ query.sql\n\
+             @@ -1,4 +1,4 @@\n-a\n+A\n b\n c\n d\n\
+             @@ -9,4 +9,4 @@\n i\n j\n k\n-l\n+L\n"
+        );
+    }
+
+    #[test]
+    fn marks_a_missing_final_newline() {
+        assert_eq!(
+            unified_diff("query.sql", "select   1", "select 1\n", false),
+            "--- query.sql\n+++ query.sql\n\
+             @@ -1 +1 @@\n-select   1\n\\ No newline at end of file\n+select 1\n"
+        );
+    }
+
+    #[test]
+    fn colors_only_when_styled() {
+        let plain = unified_diff("query.sql", "select   1\n", "select 1\n", false);
+        let styled = unified_diff("query.sql", "select   1\n", "select 1\n", true);
+
+        assert!(!plain.contains('\x1b'), "{plain:?}");
+        assert!(styled.contains(&format!("{RED}-select   1{RESET}\n")), "{styled:?}");
+        assert!(styled.contains(&format!("{GREEN}+select 1{RESET}\n")), "{styled:?}");
+        assert!(styled.contains(&format!("{CYAN}@@ -1 +1 @@{RESET}\n")), "{styled:?}");
+    }
+}
@@ -1,14 +1,16 @@
 use std::fmt;
 
 use tidysql_config::Dialect;
+pub use tidysql_formatter::FormatError;
 pub use tidysql_lints::{Diagnostic, Severity};
-use tidysql_syntax::{DialectKind, EditError, ParseError, TextEdit};
+use tidysql_syntax::{DialectKind, EditError, ParseError, SyntaxTree, TextEdit, TextRange};
 
 const CODE_UNKNOWN_DIALECT: &str = "unknown_dialect";
 const CODE_LEX_ERROR: &str = "lex_error";
 const CODE_PARSE_ERROR: &str = "parse_error";
 const CODE_UNPARSABLE: &str = "unparsable";
 const CODE_PANIC: &str = "parser_panic";
+const CODE_UNCLOSED_FORMAT_OFF: &str = "unclosed_format_off";
 
 #[derive(Debug)]
 pub enum FixError {
@@ -38,14 +40,56 @@ fn check_with_dialect(
     config: &tidysql_config::Config,
 ) -> Vec<Diagnostic> {
     match tidysql_syntax::parse(source, dialect) {
-        Ok(tree) => tidysql_lints::run(dialect, &tree, config),
+        Ok(tree) => {
+            let mut diagnostics = tidysql_lints::run(dialect, &tree, config);
+            diagnostics.extend(format_warnings(&tree));
+            diagnostics
+        }
         Err(error) => diagnostics_from_parse_error(error),
     }
 }
 
-pub fn format_with_config(source: &str, config: &tidysql_config::Config) -> String {
+/// What formatting `source` quietly leaves alone, such as the rest of the file after a
+/// `tidysql: format off` that is never closed. Sources that do not parse have none, since
+/// formatting them fails instead.
+pub fn format_warnings_with_config(
+    source: &str,
+    config: &tidysql_config::Config,
+) -> Vec<Diagnostic> {
+    let dialect = config_dialect(config);
+    match tidysql_syntax::parse(source, dialect) {
+        Ok(tree) => format_warnings(&tree),
+        Err(_) => Vec::new(),
+    }
+}
+
+fn format_warnings(tree: &SyntaxTree) -> Vec<Diagnostic> {
+    let Some(range) = tidysql_formatter::unclosed_format_off(tree) else {
+        return Vec::new();
+    };
+    vec![Diagnostic::from_text_range(
+        CODE_UNCLOSED_FORMAT_OFF,
+        "`tidysql: format off` is never closed; the rest of the file is not formatted.",
+        Severity::Warn,
+        range,
+    )]
+}
+
+pub fn format_with_config(
+    source: &str,
+    config: &tidysql_config::Config,
+) -> Result<String, FormatError> {
+    let dialect = config_dialect(config);
+    tidysql_formatter::format_with_dialect(source, dialect, &config.format)
+}
+
+pub fn format_range_with_config(
+    source: &str,
+    range: TextRange,
+    config: &tidysql_config::Config,
+) -> Result<Vec<TextEdit>, FormatError> {
     let dialect = config_dialect(config);
-    tidysql_formatter::format_with_dialect(source, dialect)
+    tidysql_formatter::format_range(source, range, dialect, &config.format)
 }
 
 pub fn fix_with_config(source: &str, config: &tidysql_config::Config) -> Result<String, FixError> {
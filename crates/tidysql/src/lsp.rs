@@ -8,9 +8,10 @@ use tower_lsp::jsonrpc::Result;
 use tower_lsp::lsp_types::{
     Diagnostic as LspDiagnostic, DiagnosticSeverity, DidChangeTextDocumentParams,
     DidCloseTextDocumentParams, DidOpenTextDocumentParams, DidSaveTextDocumentParams,
-    DocumentFormattingParams, InitializeParams, InitializeResult, InitializedParams, MessageType,
-    NumberOrString, OneOf, Position, Range as LspRange, ServerCapabilities, ServerInfo,
-    TextDocumentSyncCapability, TextDocumentSyncKind, TextEdit, Url,
+    DocumentFormattingParams, DocumentRangeFormattingParams, InitializeParams, InitializeResult,
+    InitializedParams, MessageType, NumberOrString, OneOf, Position, Range as LspRange,
+    ServerCapabilities, ServerInfo, TextDocumentSyncCapability, TextDocumentSyncKind, TextEdit,
+    Url,
 };
 use tower_lsp::{Client, LanguageServer, LspService, Server};
 
@@ -67,6 +68,22 @@ impl Backend {
         }
     }
 
+    /// Tell the user why a formatting request left the document unchanged.
+    async fn report_format_error(&self, uri: &Url, error: &tidysql::FormatError) {
+        self.client
+            .show_message(MessageType::ERROR, format!("Failed to format {uri}: {error}"))
+            .await;
+    }
+
+    /// Tell the user about the parts of the document formatting left alone.
+    async fn report_format_warnings(&self, uri: &Url, text: &str, config: &tidysql_config::Config) {
+        for warning in tidysql::format_warnings_with_config(text, config) {
+            self.client
+                .show_message(MessageType::WARNING, format!("{uri}: {}", warning.message))
+                .await;
+        }
+    }
+
     async fn load_text(&self, uri: &Url) -> Option<String> {
         if let Some(text) = self.documents.read().await.get(uri).cloned() {
             return Some(text);
@@ -90,6 +107,7 @@ impl LanguageServer for Backend {
                     TextDocumentSyncKind::FULL,
                 )),
                 document_formatting_provider: Some(OneOf::Left(true)),
+                document_range_formatting_provider: Some(OneOf::Left(true)),
                 ..Default::default()
             },
         })
@@ -147,10 +165,53 @@ impl LanguageServer for Backend {
         };
 
         let config = self.load_config(&uri).await;
-        let formatted = tidysql::format_with_config(&text, &config);
+        let formatted = match tidysql::format_with_config(&text, &config) {
+            Ok(formatted) => formatted,
+            Err(error) => {
+                self.report_format_error(&uri, &error).await;
+                return Ok(None);
+            }
+        };
+        self.report_format_warnings(&uri, &text, &config).await;
         let range = full_document_range(&text);
         Ok(Some(vec![TextEdit { range, new_text: formatted }]))
     }
+
+    async fn range_formatting(
+        &self,
+        params: DocumentRangeFormattingParams,
+    ) -> Result<Option<Vec<TextEdit>>> {
+        let uri = params.text_document.uri;
+        let text = match self.load_text(&uri).await {
+            Some(text) => text,
+            None => return Ok(None),
+        };
+
+        let config = self.load_config(&uri).await;
+        let start = position_to_offset(&text, params.range.start);
+        let end = position_to_offset(&text, params.range.end);
+        let range = tidysql_syntax::TextRange::new(
+            tidysql_syntax::TextSize::new(start as u32),
+            tidysql_syntax::TextSize::new(end.max(start) as u32),
+        );
+
+        let edits = match tidysql::format_range_with_config(&text, range, &config) {
+            Ok(edits) => edits,
+            Err(error) => {
+                self.report_format_error(&uri, &error).await;
+                return Ok(None);
+            }
+        };
+        self.report_format_warnings(&uri, &text, &config).await;
+        let edits = edits
+            .into_iter()
+            .map(|edit| TextEdit {
+                range: lsp_range(edit.range.into(), &text),
+                new_text: edit.replacement,
+            })
+            .collect();
+        Ok(Some(edits))
+    }
 }
 
 fn to_lsp_diagnostic(diagnostic: &tidysql::Diagnostic, text: &str) -> Option<LspDiagnostic> {
@@ -221,3 +282,26 @@ fn offset_to_position(text: &str, offset: usize) -> Position {
 
     Position::new(line, column)
 }
+
+fn position_to_offset(text: &str, position: Position) -> usize {
+    let mut line = 0u32;
+    let mut column = 0u32;
+
+    for (index, ch) in text.char_indices() {
+        if line == position.line && column >= position.character {
+            return index;
+        }
+
+        if ch == '\n' {
+            if line == position.line {
+                return index;
+            }
+            line += 1;
+            column = 0;
+        } else if ch != '\r' {
+            column += ch.len_utf16() as u32;
+        }
+    }
+
+    text.len()
+}
@@ -6,6 +6,7 @@ use std::process;
 use annotate_snippets::{AnnotationKind, Level, Renderer, Snippet};
 use clap::{Args, Parser, Subcommand};
 
+mod diff;
 mod lsp;
 
 #[derive(Parser)]
@@ -44,8 +45,21 @@ enum Command {
 
 #[derive(Args)]
 struct FormatCommand {
+    /// Files or directories to format; directories are searched for `.sql` files
     #[arg(value_name = "PATH")]
-    path: Option<PathBuf>,
+    paths: Vec<PathBuf>,
+    /// Only format the statement or clause around these lines (1-based, inclusive)
+    #[arg(long, value_name = "START:END", value_parser = parse_line_range)]
+    range: Option<LineRange>,
+    /// Exit with an error and list the input if it is not formatted, instead of printing it
+    #[arg(long)]
+    check: bool,
+    /// Write the formatted output back to the files instead of printing it
+    #[arg(long, conflicts_with_all = ["check", "diff"])]
+    write: bool,
+    /// Print a diff of the changes formatting would make, instead of the formatted output
+    #[arg(long)]
+    diff: bool,
     #[command(flatten)]
     config_overrides: ConfigOverrideArgs,
 }
@@ -67,7 +81,51 @@ struct LspCommand {
 }
 
 struct FormatArguments {
-    path: Option<PathBuf>,
+    paths: Vec<PathBuf>,
+    range: Option<LineRange>,
+    check: bool,
+    write: bool,
+    diff: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct LineRange {
+    start: usize,
+    end: usize,
+}
+
+impl LineRange {
+    fn text_range(&self, text: &str) -> Result<tidysql_syntax::TextRange, String> {
+        let line_starts: Vec<usize> = std::iter::once(0)
+            .chain(text.match_indices('\n').map(|(index, _)| index + 1))
+            .collect();
+
+        let Some(&start) = line_starts.get(self.start - 1) else {
+            return Err(format!(
+                "line range starts at line {} but the input has {} lines",
+                self.start,
+                text.lines().count()
+            ));
+        };
+        let end =
+            line_starts.get(self.end).map_or(text.len(), |&next_line| next_line - 1).max(start);
+
+        let size = |offset: usize| tidysql_syntax::TextSize::new(offset as u32);
+        Ok(tidysql_syntax::TextRange::new(size(start), size(end)))
+    }
+}
+
+fn parse_line_range(input: &str) -> Result<LineRange, String> {
+    let invalid = || format!("invalid line range '{input}', expected START:END");
+    let (start, end) = input.split_once(':').ok_or_else(invalid)?;
+    let start: usize = start.trim().parse().map_err(|_| invalid())?;
+    let end: usize = end.trim().parse().map_err(|_| invalid())?;
+
+    if start == 0 || end < start {
+        return Err(format!("invalid line range '{input}', lines start at 1 and END >= START"));
+    }
+
+    Ok(LineRange { start, end })
 }
 
 struct CheckArguments {
@@ -98,7 +156,7 @@ impl ConfigOverrides {
         }
 
         for lint_override in &self.lint_levels {
-            apply_lint_level(config, lint_override.lint, lint_override.level);
+            *config.lints.level_mut(lint_override.lint) = lint_override.level;
         }
     }
 }
@@ -126,24 +184,6 @@ impl From<ConfigOverrideArgs> for ConfigOverrides {
     }
 }
 
-fn apply_lint_level(
-    config: &mut tidysql_config::Config,
-    lint: tidysql_config::LintName,
-    level: tidysql_config::Severity,
-) {
-    match lint {
-        tidysql_config::LintName::DisallowNames => {
-            config.lints.disallow_names.level = level;
-        }
-        tidysql_config::LintName::ExplicitUnion => {
-            config.lints.explicit_union.level = level;
-        }
-        tidysql_config::LintName::KeywordCase => {
-            config.lints.keyword_case.level = level;
-        }
-    }
-}
-
 impl ConfigArguments {
     fn from_cli_arguments(global_options: GlobalConfigArgs, overrides: ConfigOverrides) -> Self {
         Self { config_path: global_options.config, overrides }
@@ -159,7 +199,13 @@ impl ConfigArguments {
 
 impl FormatCommand {
     fn partition(self, global_options: GlobalConfigArgs) -> (FormatArguments, ConfigArguments) {
-        let cli = FormatArguments { path: self.path };
+        let cli = FormatArguments {
+            paths: self.paths,
+            range: self.range,
+            check: self.check,
+            write: self.write,
+            diff: self.diff,
+        };
         let overrides = ConfigOverrides::from(self.config_overrides);
         let config_arguments = ConfigArguments::from_cli_arguments(global_options, overrides);
         (cli, config_arguments)
@@ -192,12 +238,180 @@ fn main() {
 
 fn format(args: FormatCommand, global_options: GlobalConfigArgs) -> Result<(), String> {
     let (cli, config_arguments) = args.partition(global_options);
-    let input = read_input(cli.path.as_deref()).map_err(|err| err.to_string())?;
-    let source_path = cli.path.as_deref().unwrap_or_else(|| Path::new("."));
+
+    if cli.paths.is_empty() {
+        if cli.write {
+            return Err("`--write` needs at least one path".to_string());
+        }
+        let input = read_input(None).map_err(|err| err.to_string())?;
+        let formatted = format_input(&input, Path::new("."), "<stdin>", &cli, &config_arguments)?;
+        return report_formatted(&cli, "<stdin>", &input, &formatted);
+    }
+
+    let files = collect_sql_files(&cli.paths)?;
+    if files.len() > 1 && cli.range.is_some() {
+        return Err("`--range` can only be used with a single file".to_string());
+    }
+    if files.len() > 1 && !(cli.write || cli.check || cli.diff) {
+        return Err("formatting several files needs `--write`, `--check` or `--diff`".to_string());
+    }
+
+    let mut reformatted = 0;
+    let mut unchanged = 0;
+    let mut failed = false;
+
+    for path in &files {
+        let display_path = path.display().to_string();
+        let result = read_input(Some(path)).map_err(|err| err.to_string()).and_then(|input| {
+            let formatted = format_input(&input, path, &display_path, &cli, &config_arguments)?;
+            Ok((input, formatted))
+        });
+        let (input, formatted) = match result {
+            Ok(result) => result,
+            Err(message) => {
+                eprintln!("{display_path}: {message}");
+                failed = true;
+                continue;
+            }
+        };
+
+        if !cli.write {
+            if report_formatted(&cli, &display_path, &input, &formatted).is_err() {
+                failed = true;
+            }
+            continue;
+        }
+
+        if formatted == input {
+            unchanged += 1;
+        } else if let Err(err) = write_atomically(path, &formatted) {
+            eprintln!("{display_path}: {err}");
+            failed = true;
+        } else {
+            reformatted += 1;
+        }
+    }
+
+    if cli.write {
+        println!(
+            "{} reformatted, {} left unchanged",
+            files_count(reformatted),
+            files_count(unchanged)
+        );
+    }
+
+    if failed { Err(String::new()) } else { Ok(()) }
+}
+
+/// Format one input the way the command line asks for, keeping its line endings, and warn
+/// about the parts formatting leaves alone.
+fn format_input(
+    input: &str,
+    source_path: &Path,
+    display_path: &str,
+    cli: &FormatArguments,
+    config_arguments: &ConfigArguments,
+) -> Result<String, String> {
     let config = config_arguments.load_config(source_path)?;
 
-    let formatted = tidysql::format_with_config(&input, &config);
-    write_output(&formatted).map_err(|err| err.to_string())
+    let formatted = match cli.range {
+        Some(lines) => {
+            let range = lines.text_range(input)?;
+            let edits = tidysql::format_range_with_config(input, range, &config)
+                .map_err(|err| err.to_string())?;
+            tidysql_syntax::apply_edits(input, edits)
+                .map_err(|err| format!("failed to apply formatting: {err:?}"))?
+        }
+        None => tidysql::format_with_config(input, &config).map_err(|err| err.to_string())?,
+    };
+    emit_diagnostics(display_path, input, &tidysql::format_warnings_with_config(input, &config));
+
+    if input.contains("\r\n") {
+        Ok(formatted.replace("\r\n", "\n").replace('\n', "\r\n"))
+    } else {
+        Ok(formatted)
+    }
+}
+
+/// Print the formatted text, or with `--check` and `--diff` report whether it differs from the
+/// input, failing if it does.
+fn report_formatted(
+    cli: &FormatArguments,
+    display_path: &str,
+    input: &str,
+    formatted: &str,
+) -> Result<(), String> {
+    if !cli.check && !cli.diff {
+        return write_output(formatted).map_err(|err| err.to_string());
+    }
+    if formatted == input {
+        return Ok(());
+    }
+
+    if cli.diff {
+        let styled = io::stdout().is_terminal();
+        print!("{}", diff::unified_diff(display_path, input, formatted, styled));
+    }
+    if cli.check {
+        println!("Would reformat: {display_path}");
+    }
+    Err(String::new())
+}
+
+/// Expand directories into the `.sql` files below them, in a stable order. Paths given
+/// explicitly are kept whatever their extension.
+fn collect_sql_files(paths: &[PathBuf]) -> Result<Vec<PathBuf>, String> {
+    let mut files = Vec::new();
+    for path in paths {
+        if path.is_dir() {
+            collect_dir(path, &mut files).map_err(|err| format!("{}: {err}", path.display()))?;
+        } else {
+            files.push(path.clone());
+        }
+    }
+    Ok(files)
+}
+
+fn collect_dir(dir: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    let mut entries = std::fs::read_dir(dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<io::Result<Vec<_>>>()?;
+    entries.sort();
+
+    for path in entries {
+        let hidden = path.file_name().is_some_and(|name| name.to_string_lossy().starts_with('.'));
+        if hidden {
+            continue;
+        }
+        if path.is_dir() {
+            collect_dir(&path, files)?;
+        } else if path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("sql")) {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Replace `path` with `contents` by writing a temporary file next to it and renaming it over
+/// the original, so that readers never see a partially written file. A symlink is resolved
+/// first so that the file it points to is rewritten rather than the link itself.
+fn write_atomically(path: &Path, contents: &str) -> io::Result<()> {
+    let path = &std::fs::canonicalize(path)?;
+    let permissions = std::fs::metadata(path)?.permissions();
+    let file_name = path.file_name().map(|name| name.to_string_lossy()).unwrap_or_default();
+    let temporary = path.with_file_name(format!(".{file_name}.tidysql-{}.tmp", process::id()));
+
+    let result = std::fs::write(&temporary, contents)
+        .and_then(|()| std::fs::set_permissions(&temporary, permissions))
+        .and_then(|()| std::fs::rename(&temporary, path));
+    if result.is_err() {
+        let _ = std::fs::remove_file(&temporary);
+    }
+    result
+}
+
+fn files_count(count: usize) -> String {
+    if count == 1 { "1 file".to_string() } else { format!("{count} files") }
 }
 
 fn check(args: CheckCommand, global_options: GlobalConfigArgs) -> Result<(), String> {
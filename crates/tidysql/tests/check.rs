@@ -0,0 +1,75 @@
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+use tidysql::Severity;
+use tidysql_config::Config;
+
+fn tidysql(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_tidysql"))
+        .args(args)
+        .current_dir(env!("CARGO_TARGET_TMPDIR"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to start tidysql");
+    child.stdin.take().unwrap().write_all(stdin.as_bytes()).unwrap();
+    child.wait_with_output().expect("failed to run tidysql")
+}
+
+#[test]
+fn warns_about_a_format_off_that_is_never_closed() {
+    let source = "select 1;\n-- tidysql: format off\nselect   2;\n";
+    let diagnostics = tidysql::check_with_config(source, &Config::default());
+
+    assert_eq!(diagnostics.len(), 1, "{diagnostics:?}");
+    let diagnostic = &diagnostics[0];
+    assert_eq!(diagnostic.code, "unclosed_format_off");
+    assert_eq!(
+        diagnostic.message,
+        "`tidysql: format off` is never closed; the rest of the file is not formatted."
+    );
+    assert_eq!(diagnostic.severity, Severity::Warn);
+    assert_eq!(&source[diagnostic.range.clone()], "-- tidysql: format off");
+}
+
+#[test]
+fn accepts_a_format_off_that_is_closed() {
+    let source = "-- tidysql: format off\nselect   1;\n-- tidysql: format on\nselect 2;\n";
+    let diagnostics = tidysql::check_with_config(source, &Config::default());
+
+    assert!(diagnostics.is_empty(), "{diagnostics:?}");
+}
+
+#[test]
+fn format_warns_about_a_format_off_that_is_never_closed() {
+    let source = "select 1;\n-- tidysql: format off\nselect   2;\n";
+    let warnings = tidysql::format_warnings_with_config(source, &Config::default());
+
+    assert_eq!(warnings.len(), 1, "{warnings:?}");
+    assert_eq!(warnings[0].code, "unclosed_format_off");
+    assert_eq!(&source[warnings[0].range.clone()], "-- tidysql: format off");
+
+    let output = tidysql(&["format"], source);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert_eq!(output.status.code(), Some(0), "{stderr}");
+    assert!(String::from_utf8_lossy(&output.stdout).ends_with("select   2;\n"));
+    assert!(stderr.contains("unclosed_format_off"), "{stderr}");
+    assert!(stderr.contains("<stdin>"), "{stderr}");
+    assert!(
+        stderr.contains(
+            "`tidysql: format off` is never closed; the rest of the file is not formatted."
+        ),
+        "{stderr}"
+    );
+}
+
+#[test]
+fn format_is_quiet_about_a_format_off_that_is_closed() {
+    let source = "-- tidysql: format off\nselect   1;\n-- tidysql: format on\nselect 2;\n";
+    let output = tidysql(&["format"], source);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(String::from_utf8_lossy(&output.stderr), "");
+}
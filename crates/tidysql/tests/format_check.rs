@@ -0,0 +1,43 @@
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+fn tidysql(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_tidysql"))
+        .args(args)
+        .current_dir(env!("CARGO_TARGET_TMPDIR"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to start tidysql");
+    child.stdin.take().unwrap().write_all(stdin.as_bytes()).unwrap();
+    child.wait_with_output().expect("failed to run tidysql")
+}
+
+#[test]
+fn check_fails_when_the_input_would_change() {
+    let output = tidysql(&["format", "--check"], "select   id from orders\n");
+
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "Would reformat: <stdin>\n");
+}
+
+#[test]
+fn check_passes_when_the_input_is_formatted() {
+    let output = tidysql(&["format", "--check"], "select id from orders\n");
+
+    assert_eq!(output.status.code(), Some(0), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "");
+}
+
+#[test]
+fn diff_goes_to_stdout() {
+    let output = tidysql(&["format", "--diff"], "select   id from orders\n");
+
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "--- <stdin>\n+++ <stdin>\n@@ -1 +1 @@\n-select   id from orders\n+select id from orders\n"
+    );
+    assert_eq!(String::from_utf8_lossy(&output.stderr), "");
+}
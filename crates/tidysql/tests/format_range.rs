@@ -0,0 +1,52 @@
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+fn tidysql(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_tidysql"))
+        .args(args)
+        .current_dir(env!("CARGO_TARGET_TMPDIR"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to start tidysql");
+    child.stdin.take().unwrap().write_all(stdin.as_bytes()).unwrap();
+    child.wait_with_output().expect("failed to run tidysql")
+}
+
+#[test]
+fn formats_the_clause_on_the_selected_lines() {
+    let output = tidysql(
+        &["format", "--range", "2:2"],
+        "select id, amount\nfrom   orders\nwhere  amount > 100\n",
+    );
+
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "select id, amount\nfrom orders\nwhere  amount > 100\n"
+    );
+}
+
+#[test]
+fn keeps_line_breaks_inside_tokens_of_an_indented_clause() {
+    let input = "WITH notes AS (\n    select id,   'first line\nsecond line' as note from orders\n)\nSELECT * FROM notes\n";
+    let output = tidysql(&["format", "--range", "2:3"], input);
+
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "WITH notes AS (\n    select id, 'first line\nsecond line' as note from orders\n)\nSELECT * FROM notes\n"
+    );
+}
+
+#[test]
+fn rejects_a_range_past_the_end_of_the_input() {
+    let output = tidysql(&["format", "--range", "5:6"], "SELECT 1\n");
+
+    assert!(!output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr),
+        "line range starts at line 5 but the input has 1 lines\n"
+    );
+}
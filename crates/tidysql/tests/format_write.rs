@@ -0,0 +1,118 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+fn tidysql(args: &[&str], dir: &Path) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_tidysql"))
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .expect("failed to run tidysql")
+}
+
+/// An empty directory for one test to write its files to.
+fn test_dir(name: &str) -> PathBuf {
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("format_write").join(name);
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn stdout(output: &Output) -> String {
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+#[test]
+fn rewrites_the_file_in_place() {
+    let dir = test_dir("rewrites_the_file_in_place");
+    fs::write(dir.join("query.sql"), "select   id,amount from   orders\n").unwrap();
+
+    let output = tidysql(&["format", "--write", "query.sql"], &dir);
+
+    assert_eq!(stdout(&output), "1 file reformatted, 0 files left unchanged\n");
+    assert_eq!(
+        fs::read_to_string(dir.join("query.sql")).unwrap(),
+        "select id, amount from orders\n"
+    );
+}
+
+#[test]
+fn leaves_formatted_files_alone() {
+    let dir = test_dir("leaves_formatted_files_alone");
+    fs::write(dir.join("formatted.sql"), "select id from orders\n").unwrap();
+    fs::write(dir.join("messy.sql"), "select   id from orders\n").unwrap();
+    let modified = fs::metadata(dir.join("formatted.sql")).unwrap().modified().unwrap();
+
+    let output = tidysql(&["format", "--write", "formatted.sql", "messy.sql"], &dir);
+
+    assert_eq!(stdout(&output), "1 file reformatted, 1 file left unchanged\n");
+    assert_eq!(fs::metadata(dir.join("formatted.sql")).unwrap().modified().unwrap(), modified);
+    assert_eq!(fs::read_to_string(dir.join("formatted.sql")).unwrap(), "select id from orders\n");
+}
+
+#[test]
+fn keeps_crlf_line_endings() {
+    let dir = test_dir("keeps_crlf_line_endings");
+    fs::write(dir.join("query.sql"), "select   id\r\nfrom   orders\r\n").unwrap();
+
+    let output = tidysql(&["format", "--write", "query.sql"], &dir);
+
+    assert_eq!(stdout(&output), "1 file reformatted, 0 files left unchanged\n");
+    assert_eq!(fs::read_to_string(dir.join("query.sql")).unwrap(), "select id from orders\r\n");
+}
+
+#[cfg(unix)]
+#[test]
+fn keeps_the_file_permissions() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = test_dir("keeps_the_file_permissions");
+    let path = dir.join("query.sql");
+    fs::write(&path, "select   id from orders\n").unwrap();
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o640)).unwrap();
+
+    let output = tidysql(&["format", "--write", "query.sql"], &dir);
+
+    assert_eq!(stdout(&output), "1 file reformatted, 0 files left unchanged\n");
+    assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o640);
+}
+
+#[cfg(unix)]
+#[test]
+fn writes_through_a_symlink() {
+    let dir = test_dir("writes_through_a_symlink");
+    fs::write(dir.join("target.sql"), "select   id from orders\n").unwrap();
+    std::os::unix::fs::symlink("target.sql", dir.join("link.sql")).unwrap();
+
+    let output = tidysql(&["format", "--write", "link.sql"], &dir);
+
+    assert_eq!(stdout(&output), "1 file reformatted, 0 files left unchanged\n");
+    assert!(fs::symlink_metadata(dir.join("link.sql")).unwrap().file_type().is_symlink());
+    assert_eq!(fs::read_to_string(dir.join("target.sql")).unwrap(), "select id from orders\n");
+}
+
+#[test]
+fn walks_directories_for_sql_files() {
+    let dir = test_dir("walks_directories_for_sql_files");
+    fs::create_dir_all(dir.join("models/staging")).unwrap();
+    fs::write(dir.join("models/orders.sql"), "select   id from orders\n").unwrap();
+    fs::write(dir.join("models/staging/customers.SQL"), "select   id from customers\n").unwrap();
+    fs::write(dir.join("models/notes.txt"), "select   id from notes\n").unwrap();
+
+    let output = tidysql(&["format", "--write", "models"], &dir);
+
+    assert_eq!(stdout(&output), "2 files reformatted, 0 files left unchanged\n");
+    assert_eq!(
+        fs::read_to_string(dir.join("models/orders.sql")).unwrap(),
+        "select id from orders\n"
+    );
+    assert_eq!(
+        fs::read_to_string(dir.join("models/staging/customers.SQL")).unwrap(),
+        "select id from customers\n"
+    );
+    assert_eq!(
+        fs::read_to_string(dir.join("models/notes.txt")).unwrap(),
+        "select   id from notes\n"
+    );
+}
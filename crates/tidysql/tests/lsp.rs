@@ -0,0 +1,214 @@
+use std::io::{BufRead, BufReader, Read, Write};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+
+/// A `tidysql lsp` process spoken to over stdio.
+struct Server {
+    child: Child,
+    stdin: Option<ChildStdin>,
+    stdout: BufReader<ChildStdout>,
+    /// Notifications received while waiting for responses.
+    notifications: Vec<String>,
+}
+
+impl Server {
+    fn start() -> Self {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_tidysql"))
+            .arg("lsp")
+            .current_dir(env!("CARGO_TARGET_TMPDIR"))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .expect("failed to start tidysql lsp");
+        let stdin = child.stdin.take();
+        let stdout = BufReader::new(child.stdout.take().unwrap());
+        let mut server = Self { child, stdin, stdout, notifications: Vec::new() };
+
+        server.request(1, "initialize", r#"{"capabilities":{}}"#);
+        server.notify("initialized", "{}");
+        server
+    }
+
+    fn send(&mut self, message: &str) {
+        let stdin = self.stdin.as_mut().unwrap();
+        write!(stdin, "Content-Length: {}\r\n\r\n{message}", message.len()).unwrap();
+        stdin.flush().unwrap();
+    }
+
+    fn notify(&mut self, method: &str, params: &str) {
+        self.send(&format!(r#"{{"jsonrpc":"2.0","method":"{method}","params":{params}}}"#));
+    }
+
+    /// Send a request and return the body of its response, skipping the notifications the
+    /// server sends in between.
+    fn request(&mut self, id: u32, method: &str, params: &str) -> String {
+        self.send(&format!(
+            r#"{{"jsonrpc":"2.0","id":{id},"method":"{method}","params":{params}}}"#
+        ));
+        self.response(id)
+    }
+
+    fn response(&mut self, id: u32) -> String {
+        loop {
+            let message = self.receive();
+            if message.contains(r#""method""#) {
+                self.notifications.push(message);
+            } else if message.contains(&format!(r#""id":{id}"#)) {
+                return message;
+            }
+        }
+    }
+
+    fn receive(&mut self) -> String {
+        let mut length = 0;
+        loop {
+            let mut header = String::new();
+            let read = self.stdout.read_line(&mut header).unwrap();
+            assert!(read > 0, "the server closed its output");
+            let header = header.trim_end();
+            if header.is_empty() {
+                break;
+            }
+            if let Some(value) = header.strip_prefix("Content-Length: ") {
+                length = value.parse().unwrap();
+            }
+        }
+
+        let mut body = vec![0; length];
+        self.stdout.read_exact(&mut body).unwrap();
+        String::from_utf8(body).unwrap()
+    }
+
+    fn open(&mut self, uri: &str, text: &str) {
+        self.notify(
+            "textDocument/didOpen",
+            &format!(
+                r#"{{"textDocument":{{"uri":"{uri}","languageId":"sql","version":1,"text":{}}}}}"#,
+                json_string(text)
+            ),
+        );
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        self.send(r#"{"jsonrpc":"2.0","id":0,"method":"shutdown"}"#);
+        self.response(0);
+        self.send(r#"{"jsonrpc":"2.0","method":"exit"}"#);
+        // The server only stops once its input is closed.
+        drop(self.stdin.take());
+        let _ = self.child.wait();
+    }
+}
+
+fn json_string(text: &str) -> String {
+    let escaped = text.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n");
+    format!("\"{escaped}\"")
+}
+
+/// Parameters of a range formatting request selecting lines `start_line` to `end_line`
+/// (0-based, inclusive).
+fn range_formatting(uri: &str, start_line: u32, end_line: u32) -> String {
+    let end_line = end_line + 1;
+    format!(
+        r#"{{"textDocument":{{"uri":"{uri}"}},"range":{{"start":{{"line":{start_line},"character":0}},"end":{{"line":{end_line},"character":0}}}},"options":{{"tabSize":4,"insertSpaces":true}}}}"#
+    )
+}
+
+#[test]
+fn range_formatting_replaces_the_selected_clause() {
+    let mut server = Server::start();
+    let uri = "file:///query.sql";
+    server.open(uri, "select id, amount\nfrom   orders\nwhere  amount > 100\n");
+
+    let response = server.request(2, "textDocument/rangeFormatting", &range_formatting(uri, 1, 1));
+
+    assert!(
+        response.contains(
+            r#""result":[{"newText":"from orders","range":{"end":{"character":13,"line":1},"start":{"character":0,"line":1}}}]"#
+        ),
+        "{response}"
+    );
+}
+
+#[test]
+fn range_formatting_returns_no_edits_for_formatted_code() {
+    let mut server = Server::start();
+    let uri = "file:///query.sql";
+    server.open(uri, "SELECT id\nFROM orders\n");
+
+    let response = server.request(2, "textDocument/rangeFormatting", &range_formatting(uri, 1, 1));
+
+    assert!(response.contains(r#""result":[]"#), "{response}");
+}
+
+#[test]
+fn range_formatting_keeps_line_breaks_inside_tokens() {
+    let mut server = Server::start();
+    let uri = "file:///query.sql";
+    server.open(
+        uri,
+        "WITH notes AS (\n    select id,   'first line\nsecond line' as note from orders\n)\nSELECT * FROM notes\n",
+    );
+
+    let response = server.request(2, "textDocument/rangeFormatting", &range_formatting(uri, 1, 2));
+
+    assert!(
+        response
+            .contains(r#""newText":"select id, 'first line\nsecond line' as note from orders""#),
+        "{response}"
+    );
+}
+
+#[test]
+fn formatting_reports_why_it_failed() {
+    let mut server = Server::start();
+    let uri = "file:///query.sql";
+    server.open(uri, "SELECT FROM WHERE (\n");
+
+    let response = server.request(
+        2,
+        "textDocument/formatting",
+        &format!(
+            r#"{{"textDocument":{{"uri":"{uri}"}},"options":{{"tabSize":4,"insertSpaces":true}}}}"#
+        ),
+    );
+
+    assert!(response.contains(r#""result":null"#), "{response}");
+    assert!(
+        server.notifications.iter().any(|message| {
+            message.contains(r#""method":"window/showMessage""#)
+                && message.contains("Failed to format file:///query.sql")
+        }),
+        "{:?}",
+        server.notifications
+    );
+}
+
+#[test]
+fn formatting_warns_about_a_format_off_that_is_never_closed() {
+    let mut server = Server::start();
+    let uri = "file:///query.sql";
+    server.open(uri, "select 1;\n-- tidysql: format off\nselect   2;\n");
+
+    let response = server.request(
+        2,
+        "textDocument/formatting",
+        &format!(
+            r#"{{"textDocument":{{"uri":"{uri}"}},"options":{{"tabSize":4,"insertSpaces":true}}}}"#
+        ),
+    );
+
+    assert!(response.contains(r#""newText":"#), "{response}");
+    assert!(
+        server.notifications.iter().any(|message| {
+            message.contains(r#""method":"window/showMessage""#)
+                && message.contains(r#""type":2"#)
+                && message.contains(
+                    "file:///query.sql: `tidysql: format off` is never closed; the rest of the file is not formatted.",
+                )
+        }),
+        "{:?}",
+        server.notifications
+    );
+}